@@ -0,0 +1,492 @@
+//! Writing events on a background thread.
+
+use std::future::Future;
+use std::io::{self, Write};
+use std::pin::Pin;
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::proto::tensorboard as pb;
+use crate::writer::Writer;
+use crate::{AsyncSummaryWrite, SummaryWrite};
+
+/// A message from an [`AsyncEventWriter`] to its worker thread.
+enum Message {
+    Event(pb::Event),
+    Summary(SystemTime, i64, pb::Summary),
+}
+
+/// Tasks waiting in [`AsyncEventWriter::write_summary_async`] for room in a full queue. The
+/// worker wakes them all whenever it takes an event, and once more when it stops.
+#[derive(Default)]
+struct Waiters(Mutex<Vec<Waker>>);
+
+impl Waiters {
+    fn register(&self, waker: &Waker) {
+        self.0
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(waker.clone());
+    }
+
+    fn wake_all(&self) {
+        let wakers = std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()));
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// Wakes all waiters when dropped, even if the worker panics.
+struct WakeOnDrop(Arc<Waiters>);
+
+impl Drop for WakeOnDrop {
+    fn drop(&mut self) {
+        self.0.wake_all();
+    }
+}
+
+/// A [`Writer`] that serializes and writes events on a background thread, like TensorFlow's
+/// Python `EventFileWriter`, so that the training loop doesn't block on disk I/O.
+///
+/// Events are sent to the worker over a bounded queue. Sending only blocks when the queue is
+/// full, i.e., when the worker has fallen `capacity` events behind. The worker flushes the writer
+/// whenever it has written something and `flush_interval` has passed since the last flush, and
+/// once more when it's done.
+///
+/// The `write_*` methods here are ordinary blocking functions. From async code, use
+/// [`write_summary_async`][Self::write_summary_async] (or the [`AsyncSummaryWrite`] trait)
+/// instead, which waits for room in the queue without blocking the thread.
+///
+/// Dropping an `AsyncEventWriter` waits for the worker to write everything that's been queued and
+/// ignores any errors. Call [`close`][Self::close] to see them.
+///
+/// ```no_run
+/// use std::time::{Duration, SystemTime};
+///
+/// use tensorboard_writer::{AsyncEventWriter, SummaryBuilder, TensorboardWriter};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let writer = TensorboardWriter::new_initialized("logs")?;
+/// let writer = AsyncEventWriter::new(writer, 1024, Duration::from_secs(2));
+/// for step in 0..1000 {
+///     let summ = SummaryBuilder::new().scalar("loss", 1.0 / (step + 1) as f32).build();
+///     writer.write_summary(SystemTime::now(), step, summ)?;
+/// }
+/// writer.close()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct AsyncEventWriter<W> {
+    /// Always `Some` until the writer is closed or dropped.
+    sender: Option<SyncSender<Message>>,
+    /// Always `Some` until the writer is closed or dropped.
+    worker: Option<JoinHandle<io::Result<Writer<W>>>>,
+    waiters: Arc<Waiters>,
+    capacity: usize,
+}
+
+impl<W: Write + Send + 'static> AsyncEventWriter<W> {
+    /// Moves `writer` to a new worker thread, with a queue of up to `capacity` events.
+    ///
+    /// A `capacity` of 0 makes every send wait until the worker takes the event, which is rarely
+    /// what you want.
+    ///
+    /// A `flush_interval` of [`Duration::ZERO`] flushes after every event.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the thread can't be spawned, as with [`std::thread::spawn`].
+    pub fn new(writer: Writer<W>, capacity: usize, flush_interval: Duration) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(capacity);
+        let waiters = Arc::new(Waiters::default());
+        let worker_waiters = Arc::clone(&waiters);
+        let worker = std::thread::Builder::new()
+            .name("tensorboard-writer".to_string())
+            .spawn(move || {
+                // Declared before the receiver, so that it's dropped after it: a waiter woken at
+                // the end must see the queue disconnected, not full.
+                let _wake_on_drop = WakeOnDrop(Arc::clone(&worker_waiters));
+                let receiver = receiver;
+                let mut writer = writer;
+                let mut last_flush = Instant::now();
+                loop {
+                    // With no interval, every event is flushed as soon as it's written, so there's
+                    // no deadline to wake up for.
+                    let message = if flush_interval == Duration::ZERO {
+                        receiver.recv().map_err(|_| RecvTimeoutError::Disconnected)
+                    } else {
+                        receiver.recv_timeout(flush_interval.saturating_sub(last_flush.elapsed()))
+                    };
+                    if message.is_ok() {
+                        worker_waiters.wake_all();
+                    }
+                    match message {
+                        Ok(Message::Event(event)) => writer.write_event(&event)?,
+                        Ok(Message::Summary(wall_time, step, summary)) => {
+                            writer.write_summary(wall_time, step, summary)?
+                        }
+                        Err(RecvTimeoutError::Timeout) => (),
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                    if last_flush.elapsed() >= flush_interval {
+                        writer.flush_if_dirty()?;
+                        last_flush = Instant::now();
+                    }
+                }
+                writer.flush()?;
+                Ok(writer)
+            })
+            .expect("failed to spawn writer thread");
+        AsyncEventWriter {
+            sender: Some(sender),
+            worker: Some(worker),
+            waiters,
+            capacity,
+        }
+    }
+}
+
+impl<W> AsyncEventWriter<W> {
+    /// Queues an event to be written, waiting if the queue is full.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`io::ErrorKind::BrokenPipe`] if the worker has stopped because of an earlier
+    /// write error. Call [`close`][Self::close] to get that error.
+    pub fn write_event(&self, event: pb::Event) -> io::Result<()> {
+        self.send(Message::Event(event))
+    }
+
+    /// Queues a summary to be written like [`Writer::write_summary`], waiting if the queue is
+    /// full.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`write_event`][Self::write_event].
+    pub fn write_summary(
+        &self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> io::Result<()> {
+        self.send(Message::Summary(wall_time, step, summary))
+    }
+
+    /// Queues a summary to be written like [`write_summary`][Self::write_summary], but from async
+    /// code: if the queue is full, the returned future waits for room without blocking the
+    /// thread.
+    ///
+    /// With a `capacity` of 0, there's no queue to wait for room in, so this blocks like
+    /// `write_summary` until the worker takes the summary.
+    ///
+    /// # Errors
+    ///
+    /// Fails under the same conditions as [`write_event`][Self::write_event].
+    pub fn write_summary_async(
+        &self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> impl Future<Output = io::Result<()>> + Send + '_ {
+        let message = Message::Summary(wall_time, step, summary);
+        let (message, result) = if self.capacity == 0 {
+            (None, Some(self.send(message)))
+        } else {
+            (Some(message), None)
+        };
+        SendFuture {
+            sender: self.sender(),
+            waiters: &self.waiters,
+            message,
+            result,
+        }
+    }
+
+    fn sender(&self) -> &SyncSender<Message> {
+        self.sender.as_ref().expect("sender taken before drop")
+    }
+
+    fn send(&self, message: Message) -> io::Result<()> {
+        self.sender().send(message).map_err(|_| broken_pipe())
+    }
+
+    /// Waits for the worker to write and flush all queued events, and returns the underlying
+    /// writer.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error that the worker hit, if any. Events queued after that error were
+    /// dropped.
+    ///
+    /// # Panics
+    ///
+    /// If the worker panicked, resumes the panic on this thread.
+    pub fn close(mut self) -> io::Result<Writer<W>> {
+        match self.join() {
+            Ok(result) => result,
+            Err(panic) => std::panic::resume_unwind(panic),
+        }
+    }
+
+    /// Waits for the worker to finish, returning its result, or its panic payload if it panicked.
+    fn join(&mut self) -> std::thread::Result<io::Result<Writer<W>>> {
+        // Hanging up tells the worker to finish once the queue is empty.
+        drop(self.sender.take());
+        let worker = self.worker.take().expect("worker taken before drop");
+        worker.join()
+    }
+}
+
+fn broken_pipe() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::BrokenPipe,
+        "event writer thread stopped after an error",
+    )
+}
+
+/// The future returned by [`AsyncEventWriter::write_summary_async`].
+struct SendFuture<'a> {
+    sender: &'a SyncSender<Message>,
+    waiters: &'a Waiters,
+    /// The message to send, until it's been sent.
+    message: Option<Message>,
+    /// The result of a send that already happened, for a queue with no capacity.
+    result: Option<io::Result<()>>,
+}
+
+impl SendFuture<'_> {
+    fn try_send(&mut self) -> Poll<io::Result<()>> {
+        let message = self.message.take().expect("polled after completion");
+        match self.sender.try_send(message) {
+            Ok(()) => Poll::Ready(Ok(())),
+            Err(TrySendError::Disconnected(_)) => Poll::Ready(Err(broken_pipe())),
+            Err(TrySendError::Full(message)) => {
+                self.message = Some(message);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Future for SendFuture<'_> {
+    type Output = io::Result<()>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if let Some(result) = this.result.take() {
+            return Poll::Ready(result);
+        }
+        if let Poll::Ready(result) = this.try_send() {
+            return Poll::Ready(result);
+        }
+        // Register before trying again, so that room made in between isn't missed.
+        this.waiters.register(cx.waker());
+        this.try_send()
+    }
+}
+
+impl<W> Drop for AsyncEventWriter<W> {
+    fn drop(&mut self) {
+        // Ignore a panic on the worker, too: resuming it here would abort the process if this
+        // thread is already unwinding.
+        if self.worker.is_some() {
+            let _ = self.join();
+        }
+    }
+}
+
+impl<W> SummaryWrite for AsyncEventWriter<W> {
+    fn write_summary(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> io::Result<()> {
+        AsyncEventWriter::write_summary(self, wall_time, step, summary)
+    }
+}
+
+impl<W> AsyncSummaryWrite for AsyncEventWriter<W> {
+    fn write_summary(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> impl Future<Output = io::Result<()>> + Send {
+        self.write_summary_async(wall_time, step, summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventReader, SummaryBuilder};
+
+    /// Fails every write.
+    struct BrokenWriter;
+
+    impl Write for BrokenWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk on fire"))
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_async_event_writer() {
+        let writer = AsyncEventWriter::new(Writer::wrap(Vec::new()), 4, Duration::from_millis(1));
+        for step in 0..100 {
+            let summ = SummaryBuilder::new().scalar("loss", 1.0).build();
+            writer.write_summary(SystemTime::now(), step, summ).unwrap();
+        }
+        let bytes = writer.close().unwrap().into_vec();
+        let steps: Vec<i64> = EventReader::new(&bytes[..])
+            .map(|e| e.unwrap().step)
+            .collect();
+        assert_eq!(steps, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_async_event_writer_error() {
+        let writer = AsyncEventWriter::new(Writer::wrap(BrokenWriter), 0, Duration::from_secs(60));
+        let mut queued = 0;
+        // The first write fails on the worker, which then stops taking events.
+        while writer.write_event(pb::Event::default()).is_ok() {
+            queued += 1;
+            assert!(queued < 100, "worker didn't stop");
+        }
+        let e = writer.close().err().unwrap();
+        assert_eq!(e.to_string(), "disk on fire");
+    }
+
+    /// Counts flushes.
+    #[derive(Default)]
+    struct FlushCounter {
+        flushes: usize,
+    }
+
+    impl Write for FlushCounter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_async_event_writer_zero_flush_interval() {
+        let writer = Writer::wrap(FlushCounter::default());
+        let writer = AsyncEventWriter::new(writer, 0, Duration::ZERO);
+        for step in 0..3 {
+            let summ = SummaryBuilder::new().scalar("loss", 1.0).build();
+            writer.write_summary(SystemTime::now(), step, summ).unwrap();
+        }
+        // One flush per event, plus the final one.
+        assert_eq!(writer.close().unwrap().get_ref().flushes, 4);
+    }
+
+    /// Panics on the first write.
+    struct PanickingWriter;
+
+    impl Write for PanickingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            panic!("writer panicked");
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Runs a future to completion on this thread, parking while it's pending.
+    fn block_on<F: Future>(future: F) -> F::Output {
+        struct ThreadWaker(std::thread::Thread);
+        impl std::task::Wake for ThreadWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(future);
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+            std::thread::park();
+        }
+    }
+
+    async fn log_losses<S: AsyncSummaryWrite>(sink: &mut S, steps: i64) -> io::Result<()> {
+        for step in 0..steps {
+            let summ = SummaryBuilder::new().scalar("loss", 1.0).build();
+            sink.write_summary(SystemTime::now(), step, summ).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes slowly, so that the queue fills up.
+    struct SlowWriter(Vec<u8>);
+
+    impl Write for SlowWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            std::thread::sleep(Duration::from_micros(200));
+            self.0.write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_async_summary_write() {
+        let steps = |bytes: &[u8]| -> Vec<i64> {
+            EventReader::new(bytes).map(|e| e.unwrap().step).collect()
+        };
+
+        let mut writer = Writer::wrap(Vec::new());
+        block_on(log_losses(&mut writer, 3)).unwrap();
+        assert_eq!(steps(&writer.into_vec()), vec![0, 1, 2]);
+
+        for capacity in [0, 1, 4] {
+            let writer = Writer::wrap(SlowWriter(Vec::new()));
+            let mut writer = AsyncEventWriter::new(writer, capacity, Duration::from_secs(60));
+            block_on(log_losses(&mut writer, 20)).unwrap();
+            let bytes = writer.close().unwrap().into_inner().0;
+            assert_eq!(steps(&bytes), (0..20).collect::<Vec<_>>());
+        }
+
+        // A waiting task sees the worker stop.
+        let mut writer = AsyncEventWriter::new(Writer::wrap(BrokenWriter), 1, Duration::ZERO);
+        let e = block_on(log_losses(&mut writer, 100)).err().unwrap();
+        assert_eq!(e.kind(), io::ErrorKind::BrokenPipe);
+        assert_eq!(writer.close().err().unwrap().to_string(), "disk on fire");
+    }
+
+    #[test]
+    fn test_async_event_writer_panic() {
+        let new = || {
+            let writer = Writer::wrap(PanickingWriter);
+            let writer = AsyncEventWriter::new(writer, 0, Duration::from_secs(60));
+            let _ = writer.write_event(pb::Event::default());
+            writer
+        };
+        // Dropping swallows the worker's panic, even while this thread is unwinding.
+        drop(new());
+        let result = std::panic::catch_unwind(|| {
+            let _writer = new();
+            panic!("caller panicked");
+        });
+        let panic = result.err().unwrap();
+        assert_eq!(panic.downcast_ref::<&str>(), Some(&"caller panicked"));
+        // Closing resumes it.
+        let result = std::panic::catch_unwind(|| new().close());
+        let panic = result.err().unwrap();
+        assert_eq!(panic.downcast_ref::<&str>(), Some(&"writer panicked"));
+    }
+}
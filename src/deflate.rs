@@ -0,0 +1,56 @@
+//! Uncompressed ("stored") deflate streams, for writing compressed container formats without a
+//! compression library.
+
+/// Maximum payload of a single stored deflate block.
+pub(crate) const MAX_STORED_BLOCK: usize = 0xffff;
+
+/// Appends `data` to `out` as a raw deflate stream made of stored blocks.
+pub(crate) fn write_stored(out: &mut Vec<u8>, data: &[u8]) {
+    let mut blocks = data.chunks(MAX_STORED_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend([0x01, 0x00, 0x00, 0xff, 0xff]); // a single empty final block
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        out.push(is_final as u8); // BFINAL bit, BTYPE = 00 (stored)
+        let len = block.len() as u16;
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+}
+
+/// Wraps `data` in a gzip member (RFC 1952) with no file name or modification time.
+pub(crate) fn gzip_stored(data: &[u8]) -> Vec<u8> {
+    // magic, CM = deflate, no flags, MTIME = 0, no extra flags, OS = unknown
+    let mut out = vec![0x1f, 0x8b, 0x08, 0x00, 0, 0, 0, 0, 0x00, 0xff];
+    write_stored(&mut out, data);
+    out.extend(crc::crc32::checksum_ieee(data).to_le_bytes());
+    out.extend((data.len() as u32).to_le_bytes());
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_stored_multiple_blocks() {
+        let data = vec![7u8; MAX_STORED_BLOCK + 10];
+        let mut out = Vec::new();
+        write_stored(&mut out, &data);
+        // two block headers and data
+        assert_eq!(out.len(), 2 * 5 + data.len());
+        assert_eq!(out[0], 0x00); // first block is not final
+        assert_eq!(out[5 + MAX_STORED_BLOCK], 0x01); // second block is final
+    }
+
+    #[test]
+    fn test_gzip_stored() {
+        let gz = gzip_stored(b"hello");
+        assert_eq!(&gz[..3], b"\x1f\x8b\x08");
+        assert_eq!(&gz[10..15], b"\x01\x05\x00\xfa\xff");
+        assert_eq!(&gz[15..20], b"hello");
+        assert_eq!(&gz[20..], b"\x86\xa6\x10\x36\x05\x00\x00\x00");
+    }
+}
@@ -0,0 +1,115 @@
+//! Scalars derived from two other tracked values.
+
+use crate::summary::SummaryBuilder;
+
+/// How a [`DerivedScalar`] combines its two inputs.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Derivation {
+    /// `lhs / rhs`
+    Ratio,
+    /// `lhs - rhs`
+    Difference,
+}
+
+/// A scalar time series computed from the latest values of two others, like the ratio of
+/// validation loss to training loss.
+///
+/// Update the inputs with [`set_lhs`][Self::set_lhs] and [`set_rhs`][Self::set_rhs] whenever
+/// you compute them, and call [`log`][Self::log] when writing a summary. The inputs can be
+/// updated at different rates; the derived value always uses the most recent of each.
+///
+/// ```
+/// use tensorboard_writer::{DerivedScalar, SummaryBuilder};
+///
+/// let mut ratio = DerivedScalar::ratio("loss/val_over_train");
+/// ratio.set_lhs(0.6); // validation loss
+/// ratio.set_rhs(0.5); // training loss
+/// let summ = ratio.log(SummaryBuilder::new()).build();
+/// assert_eq!(summ.value.len(), 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DerivedScalar {
+    tag: String,
+    derivation: Derivation,
+    lhs: Option<f64>,
+    rhs: Option<f64>,
+}
+
+impl DerivedScalar {
+    /// Creates a derived scalar with the given tag and derivation, with no inputs yet.
+    pub fn new(tag: &str, derivation: Derivation) -> Self {
+        DerivedScalar {
+            tag: tag.to_string(),
+            derivation,
+            lhs: None,
+            rhs: None,
+        }
+    }
+
+    /// Creates a derived scalar that logs `lhs / rhs`.
+    pub fn ratio(tag: &str) -> Self {
+        Self::new(tag, Derivation::Ratio)
+    }
+
+    /// Creates a derived scalar that logs `lhs - rhs`.
+    pub fn difference(tag: &str) -> Self {
+        Self::new(tag, Derivation::Difference)
+    }
+
+    /// Updates the left-hand input (the numerator of a ratio).
+    pub fn set_lhs(&mut self, value: f64) {
+        self.lhs = Some(value);
+    }
+
+    /// Updates the right-hand input (the denominator of a ratio).
+    pub fn set_rhs(&mut self, value: f64) {
+        self.rhs = Some(value);
+    }
+
+    /// The current derived value, or `None` if either input hasn't been set yet. A ratio with a
+    /// zero denominator follows IEEE 754 semantics, giving an infinity or NaN.
+    pub fn value(&self) -> Option<f64> {
+        let (lhs, rhs) = (self.lhs?, self.rhs?);
+        Some(match self.derivation {
+            Derivation::Ratio => lhs / rhs,
+            Derivation::Difference => lhs - rhs,
+        })
+    }
+
+    /// Adds the current derived value to `builder` as a scalar, returning the updated builder. If
+    /// either input hasn't been set yet, the builder is returned unchanged.
+    pub fn log(&self, builder: SummaryBuilder) -> SummaryBuilder {
+        match self.value() {
+            Some(x) => builder.scalar(&self.tag, x as f32),
+            None => builder,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::tensorboard::summary::value::Value as InnerValue;
+
+    #[test]
+    fn test_ratio() {
+        let mut ratio = DerivedScalar::ratio("r");
+        assert!(ratio.log(SummaryBuilder::new()).build().value.is_empty());
+        ratio.set_lhs(3.0);
+        assert_eq!(ratio.value(), None);
+        ratio.set_rhs(4.0);
+        let summ = ratio.log(SummaryBuilder::new()).build();
+        assert_eq!(summ.value[0].tag, "r");
+        assert_eq!(summ.value[0].value, Some(InnerValue::SimpleValue(0.75)));
+        ratio.set_lhs(2.0);
+        assert_eq!(ratio.value(), Some(0.5));
+    }
+
+    #[test]
+    fn test_difference() {
+        let mut diff = DerivedScalar::difference("d");
+        diff.set_lhs(1.0);
+        diff.set_rhs(2.5);
+        assert_eq!(diff.value(), Some(-1.5));
+    }
+}
@@ -0,0 +1,887 @@
+//! Reading events back out of TensorBoard event files.
+
+use byteorder::{ByteOrder, LittleEndian};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+
+use prost::Message;
+
+use crate::masked_crc::MaskedCrc;
+use crate::proto::tensorboard as pb;
+use crate::tf_record::{ReadRecordError, TfRecord, TfRecordReader};
+use crate::writer::padding_len;
+
+/// A reader that decodes [`tensorboard.Event`][pb::Event] protos from a stream of TFRecords.
+///
+/// This is an [`Iterator`] over events. The iterator ends when the underlying stream runs dry, but
+/// since it is built on a resumable [`TfRecordReader`], it can be polled again after more data
+/// has been appended to the stream (e.g., by a training job that is still writing). In that case,
+/// a record that was only partially written will be picked up where it left off.
+///
+/// Every record's data checksum is verified before the event is decoded. Records with bad
+/// checksums or invalid payloads yield [`io::ErrorKind::InvalidData`] errors.
+#[derive(Debug)]
+pub struct EventReader<R> {
+    reader: TfRecordReader<R>,
+    /// Records are expected to start at multiples of this many bytes.
+    alignment: usize,
+    /// Bytes of records (and padding) read so far, for alignment.
+    offset: u64,
+    /// Bytes of padding still to be skipped before the next record.
+    pending_padding: usize,
+}
+
+impl EventReader<BufReader<File>> {
+    /// Opens an event file for reading from its beginning.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl<R: Read> EventReader<R> {
+    /// Creates an event reader over a stream of TFRecords. The underlying reader should be
+    /// aligned to the start of a record (usually, this is just the start of the file).
+    pub fn new(reader: R) -> Self {
+        EventReader {
+            reader: TfRecordReader::new(reader),
+            alignment: 1,
+            offset: 0,
+            pending_padding: 0,
+        }
+    }
+
+    /// Expects records to be padded with zero bytes to multiples of `alignment` bytes, as written
+    /// by a [`Writer`][crate::TensorboardWriter::with_alignment] with the same alignment. Nonzero
+    /// padding bytes yield [`io::ErrorKind::InvalidData`] errors.
+    pub fn with_alignment(mut self, alignment: usize) -> Self {
+        self.alignment = alignment.max(1);
+        self
+    }
+
+    /// Skips any padding left over from the last record. Returns `Ok(false)` if the underlying
+    /// reader runs dry first.
+    fn skip_padding(&mut self) -> io::Result<bool> {
+        let mut buf = [0u8; 64];
+        while self.pending_padding > 0 {
+            let len = self.pending_padding.min(buf.len());
+            let n = self.reader.get_mut().read(&mut buf[..len])?;
+            if n == 0 {
+                return Ok(false);
+            }
+            if buf[..n].iter().any(|&b| b != 0) {
+                return Err(invalid_data("nonzero padding between records"));
+            }
+            self.pending_padding -= n;
+        }
+        Ok(true)
+    }
+
+    /// Consumes this `EventReader<R>`, returning the underlying reader `R`.
+    pub fn into_inner(self) -> R {
+        self.reader.into_inner()
+    }
+
+    /// Reads the next event along with the data CRC of the record that it came from. The CRC is
+    /// the one stored in the file, which has been verified against the record payload.
+    ///
+    /// Returns `None` if no complete record is available yet.
+    pub fn next_with_crc(&mut self) -> Option<io::Result<(pb::Event, MaskedCrc)>> {
+        let record = match self.next_record()? {
+            Ok(record) => record,
+            Err(e) => return Some(Err(e)),
+        };
+        let event = match pb::Event::decode(&record.data[..]) {
+            Ok(event) => event,
+            Err(e) => return Some(Err(invalid_data(e))),
+        };
+        Some(Ok((event, record.data_crc)))
+    }
+}
+
+impl<R: Read> EventReader<R> {
+    /// Reads the next record and verifies its checksums, without decoding its event.
+    fn next_record(&mut self) -> Option<io::Result<TfRecord>> {
+        match self.skip_padding() {
+            Ok(true) => (),
+            Ok(false) => return None,
+            Err(e) => return Some(Err(e)),
+        }
+        let record = match self.reader.read_record() {
+            Ok(record) => record,
+            Err(ReadRecordError::Truncated) => return None,
+            Err(ReadRecordError::Io(e)) => return Some(Err(e)),
+            Err(e) => return Some(Err(invalid_data(e))),
+        };
+        // 12-byte header and 4-byte footer
+        self.offset += 16 + record.data.len() as u64;
+        self.pending_padding = padding_len(self.offset, self.alignment);
+        self.offset += self.pending_padding as u64;
+        if let Err(e) = record.checksum() {
+            return Some(Err(invalid_data(e)));
+        }
+        Some(Ok(record))
+    }
+}
+
+impl<R: Read> Iterator for EventReader<R> {
+    type Item = io::Result<pb::Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_with_crc().map(|r| r.map(|(event, _)| event))
+    }
+}
+
+/// Reads all points of the scalar time series with the given tag from an event file, as
+/// `(step, wall_time, value)` triples in file order.
+///
+/// Values of other tags, and non-scalar values with this tag, are skipped. Events are first
+/// skimmed for just their tags, so those without this tag are never fully decoded. Scalars may be
+/// stored as simple values (as written by [`SummaryBuilder::scalar`][crate::SummaryBuilder::scalar])
+/// or as rank-0 `float32`, `float64`, or `int64` tensors (as written by
+/// [`scalar_f64`][crate::SummaryBuilder::scalar_f64] and
+/// [`scalar_i64`][crate::SummaryBuilder::scalar_i64]). Values are returned as `f64` rather than
+/// `f32`, so that `float64` and `int64` scalars aren't truncated; the conversion is exact for all
+/// but very large `int64`s.
+///
+/// # Errors
+///
+/// Errors if the file can't be read, or if it contains a corrupt record.
+pub fn read_scalar_tag<P: AsRef<Path>>(path: P, tag: &str) -> io::Result<Vec<(i64, f64, f64)>> {
+    let mut result = Vec::new();
+    let mut reader = EventReader::open(path)?;
+    while let Some(record) = reader.next_record() {
+        let record = record?;
+        let tags = EventTags::decode(&record.data[..]).map_err(invalid_data)?;
+        if !tags
+            .summary
+            .is_some_and(|s| s.value.iter().any(|v| v.tag == tag))
+        {
+            continue;
+        }
+        let event = pb::Event::decode(&record.data[..]).map_err(invalid_data)?;
+        let summary = match &event.what {
+            Some(pb::event::What::Summary(summary)) => summary,
+            _ => continue,
+        };
+        for value in summary.value.iter().filter(|v| v.tag == tag) {
+            if let Some(x) = value.value.as_ref().and_then(scalar_value) {
+                result.push((event.step, event.wall_time, x));
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Truncates an event file after its last valid record, discarding any trailing partial or corrupt
+/// data (e.g., from a crash mid-write). Returns the new length of the file.
+///
+/// Records are read from the start of the file, verifying both checksums of each and decoding its
+/// event, and the file is cut at the end of the last record before the first invalid one. Records
+/// after a corrupt one are discarded as well, since there's no reliable way to find where they
+/// start.
+///
+/// This expects records to be packed back to back. For a file written with
+/// [`with_alignment`][crate::TensorboardWriter::with_alignment], use
+/// [`repair_aligned_event_file`] instead: here, the padding after the first record would look
+/// like corrupt data, and everything after it would be discarded.
+///
+/// # Errors
+///
+/// Errors if the file can't be read or truncated.
+pub fn repair_event_file<P: AsRef<Path>>(path: P) -> io::Result<u64> {
+    repair_aligned_event_file(path, 1)
+}
+
+/// Truncates an event file like [`repair_event_file`], but expects records to be padded to
+/// multiples of `alignment` bytes, as written by a
+/// [`Writer`][crate::TensorboardWriter::with_alignment] with the same alignment.
+///
+/// The file is cut after the padding of the last valid record, and that padding is filled in with
+/// zeros if it was cut short, so that appending to the file keeps records aligned.
+///
+/// # Errors
+///
+/// Errors if the file can't be read or truncated.
+pub fn repair_aligned_event_file<P: AsRef<Path>>(path: P, alignment: usize) -> io::Result<u64> {
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)?;
+    let mut reader = EventReader::new(BufReader::new(&file)).with_alignment(alignment);
+    // End of the last valid record, and of its padding.
+    let (mut record_end, mut valid_len) = (0, 0);
+    loop {
+        match reader.next_with_crc() {
+            Some(Ok(_)) => {
+                valid_len = reader.offset;
+                record_end = valid_len - reader.pending_padding as u64;
+            }
+            Some(Err(e)) if e.kind() != io::ErrorKind::InvalidData => return Err(e),
+            _ => break,
+        }
+    }
+    drop(reader);
+    // Truncating first discards any nonzero bytes where the padding should be.
+    file.set_len(record_end)?;
+    file.set_len(valid_len)?;
+    Ok(valid_len)
+}
+
+/// Scans an event file for the names of the plugins that own its summaries, i.e., the dashboards
+/// that would show its data.
+///
+/// Plugin names come from each value's [`SummaryMetadata`][pb::SummaryMetadata]. Legacy values
+/// without plugin metadata, like simple scalars and histograms, are attributed to the plugins
+/// that TensorBoard would migrate them to (`scalars`, `histograms`, `images`, and `audio`).
+///
+/// # Errors
+///
+/// Errors if the file can't be read, or if it contains a corrupt record.
+pub fn plugins_in_file<P: AsRef<Path>>(path: P) -> io::Result<HashSet<String>> {
+    use pb::summary::value::Value;
+    let mut result = HashSet::new();
+    for event in EventReader::open(path)? {
+        let summary = match event?.what {
+            Some(pb::event::What::Summary(summary)) => summary,
+            _ => continue,
+        };
+        for value in summary.value {
+            let plugin_name = value
+                .metadata
+                .and_then(|m| m.plugin_data)
+                .map(|d| d.plugin_name)
+                .filter(|name| !name.is_empty());
+            let plugin_name = match (plugin_name, &value.value) {
+                (Some(name), _) => name,
+                (None, Some(Value::SimpleValue(_))) => "scalars".to_string(),
+                (None, Some(Value::Histo(_))) => "histograms".to_string(),
+                (None, Some(Value::Image(_))) => "images".to_string(),
+                (None, Some(Value::Audio(_))) => "audio".to_string(),
+                (None, _) => continue,
+            };
+            result.insert(plugin_name);
+        }
+    }
+    Ok(result)
+}
+
+/// Lists the steps at which an event file has a summary value with the given tag, of any kind, in
+/// increasing order and without duplicates.
+///
+/// # Errors
+///
+/// Errors if the file can't be read, or if it contains a corrupt record.
+pub fn steps_for_tag<P: AsRef<Path>>(path: P, tag: &str) -> io::Result<Vec<i64>> {
+    let mut steps = Vec::new();
+    for event in EventReader::open(path)? {
+        let event = event?;
+        if let Some(pb::event::What::Summary(summary)) = &event.what {
+            if summary.value.iter().any(|v| v.tag == tag) {
+                steps.push(event.step);
+            }
+        }
+    }
+    steps.sort_unstable();
+    steps.dedup();
+    Ok(steps)
+}
+
+/// Records larger than this many bytes are flagged by [`lint_event_file`]. Some protobuf
+/// implementations refuse to parse messages past this size by default.
+pub const LINT_MAX_RECORD_LEN: u64 = 64 << 20;
+
+/// A problem found by [`lint_event_file`]. Records are identified by their index in the file,
+/// counting from zero.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintWarning {
+    /// The first record isn't a `file_version` event, so readers may not recognize the file.
+    MissingFileVersion,
+    /// A `file_version` event appears after the start of the file.
+    MisplacedFileVersion { record: usize },
+    /// A record's length failed its checksum. Nothing after it can be read, so linting stops.
+    BadLengthChecksum { record: usize },
+    /// A record's data failed its checksum.
+    BadChecksum { record: usize },
+    /// A record's data isn't a valid `Event` proto.
+    InvalidEvent { record: usize },
+    /// A record's data is longer than [`LINT_MAX_RECORD_LEN`].
+    OversizedRecord { record: usize, len: u64 },
+    /// A value's step is smaller than that of the previous value with the same tag.
+    NonMonotonicStep {
+        record: usize,
+        tag: String,
+        step: i64,
+        previous: i64,
+    },
+    /// The first value with a tag is a tensor without plugin metadata, so no dashboard will
+    /// show the time series.
+    MissingMetadata { record: usize, tag: String },
+    /// The file ends partway through a record.
+    Truncated,
+    /// The file couldn't be opened or read, so linting stops.
+    Unreadable {
+        kind: io::ErrorKind,
+        message: String,
+    },
+}
+
+/// Checks an event file for problems that would make TensorBoard reject or misread it.
+///
+/// Unlike the other functions in this module, this keeps going after a record fails its data
+/// checksum or doesn't decode, reporting each such record and moving on to the next. Only a bad
+/// length checksum stops the scan, since the records after it can't be found. Returns the
+/// warnings in file order; a clean file yields none. If the file can't be opened or read, the
+/// last warning is [`Unreadable`][LintWarning::Unreadable].
+pub fn lint_event_file<P: AsRef<Path>>(path: P) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    if let Err(e) = lint_into(path.as_ref(), &mut warnings) {
+        warnings.push(LintWarning::Unreadable {
+            kind: e.kind(),
+            message: e.to_string(),
+        });
+    }
+    warnings
+}
+
+/// Does the work of [`lint_event_file`], keeping the warnings found before any I/O error.
+fn lint_into(path: &Path, warnings: &mut Vec<LintWarning>) -> io::Result<()> {
+    use std::collections::HashMap;
+    let file = File::open(path)?;
+    let file_len = file.metadata()?.len();
+    let mut reader = TfRecordReader::new(BufReader::new(file));
+    let mut last_steps: HashMap<String, i64> = HashMap::new();
+    let mut offset = 0;
+    for record_index in 0.. {
+        let record = match reader.read_record() {
+            Ok(record) => record,
+            Err(ReadRecordError::Truncated) => {
+                if offset < file_len {
+                    warnings.push(LintWarning::Truncated);
+                }
+                break;
+            }
+            Err(ReadRecordError::BadLengthCrc(_)) => {
+                warnings.push(LintWarning::BadLengthChecksum {
+                    record: record_index,
+                });
+                break;
+            }
+            Err(ReadRecordError::TooLarge(len)) => {
+                warnings.push(LintWarning::OversizedRecord {
+                    record: record_index,
+                    len,
+                });
+                break;
+            }
+            Err(ReadRecordError::Io(e)) => return Err(e),
+        };
+        let record_len = record.data.len() as u64;
+        // 12-byte header and 4-byte footer
+        offset += 16 + record_len;
+        if record_len > LINT_MAX_RECORD_LEN {
+            warnings.push(LintWarning::OversizedRecord {
+                record: record_index,
+                len: record_len,
+            });
+        }
+        if record.checksum().is_err() {
+            warnings.push(LintWarning::BadChecksum {
+                record: record_index,
+            });
+            if record_index == 0 {
+                warnings.push(LintWarning::MissingFileVersion);
+            }
+            continue;
+        }
+        let event = match pb::Event::decode(&record.data[..]) {
+            Ok(event) => event,
+            Err(_) => {
+                warnings.push(LintWarning::InvalidEvent {
+                    record: record_index,
+                });
+                if record_index == 0 {
+                    warnings.push(LintWarning::MissingFileVersion);
+                }
+                continue;
+            }
+        };
+        match (&event.what, record_index) {
+            (Some(pb::event::What::FileVersion(_)), 0) => (),
+            (Some(pb::event::What::FileVersion(_)), _) => {
+                warnings.push(LintWarning::MisplacedFileVersion {
+                    record: record_index,
+                })
+            }
+            (_, 0) => warnings.push(LintWarning::MissingFileVersion),
+            _ => (),
+        }
+        let summary = match event.what {
+            Some(pb::event::What::Summary(summary)) => summary,
+            _ => continue,
+        };
+        for value in summary.value {
+            match last_steps.insert(value.tag.clone(), event.step) {
+                Some(previous) if event.step < previous => {
+                    warnings.push(LintWarning::NonMonotonicStep {
+                        record: record_index,
+                        tag: value.tag,
+                        step: event.step,
+                        previous,
+                    })
+                }
+                Some(_) => (),
+                None => {
+                    let has_plugin = value
+                        .metadata
+                        .and_then(|m| m.plugin_data)
+                        .is_some_and(|d| !d.plugin_name.is_empty());
+                    if let (false, Some(pb::summary::value::Value::Tensor(_))) =
+                        (has_plugin, &value.value)
+                    {
+                        warnings.push(LintWarning::MissingMetadata {
+                            record: record_index,
+                            tag: value.tag,
+                        });
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Just the tags of an event's summary values, for skimming events without decoding the values
+/// themselves. Fields that aren't declared here are skipped on the wire.
+#[derive(Clone, PartialEq, Message)]
+struct EventTags {
+    /// The `summary` variant of `Event.what`.
+    #[prost(message, optional, tag = "5")]
+    summary: Option<SummaryTags>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct SummaryTags {
+    #[prost(message, repeated, tag = "1")]
+    value: Vec<ValueTag>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ValueTag {
+    #[prost(string, tag = "1")]
+    tag: String,
+}
+
+/// Extracts a scalar from a summary value as an `f64`, if it is one.
+fn scalar_value(value: &pb::summary::value::Value) -> Option<f64> {
+    use pb::summary::value::Value;
+    use pb::DataType;
+    let t = match value {
+        Value::SimpleValue(x) => return Some(f64::from(*x)),
+        Value::Tensor(t) => t,
+        _ => return None,
+    };
+    // Only rank-0 tensors are scalars. A missing shape is rank 0, too.
+    if t.tensor_shape.iter().any(|s| !s.dim.is_empty()) {
+        return None;
+    }
+    let content = &t.tensor_content[..];
+    match DataType::from_i32(t.dtype)? {
+        DataType::DtFloat => match (&t.float_val[..], content.len()) {
+            ([x], _) => Some(f64::from(*x)),
+            ([], 4) => Some(f64::from(LittleEndian::read_f32(content))),
+            _ => None,
+        },
+        DataType::DtDouble => match (&t.double_val[..], content.len()) {
+            ([x], _) => Some(*x),
+            ([], 8) => Some(LittleEndian::read_f64(content)),
+            _ => None,
+        },
+        DataType::DtInt64 => match (&t.int64_val[..], content.len()) {
+            ([x], _) => Some(*x as f64),
+            ([], 8) => Some(LittleEndian::read_i64(content) as f64),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn invalid_data<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scripted_reader::ScriptedReader;
+    use crate::temp_dir::TempDir;
+    use crate::tf_record::TfRecord;
+    use crate::{SummaryBuilder, TensorboardWriter};
+    use std::io::Cursor;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn step_event(step: i64) -> pb::Event {
+        pb::Event {
+            step,
+            what: Some(pb::event::What::FileVersion("brain.Event:2".to_string())),
+            ..Default::default()
+        }
+    }
+
+    fn encode_record(event: &pb::Event) -> Vec<u8> {
+        let mut buf = Vec::new();
+        TfRecord::from_data(event.encode_to_vec())
+            .write(&mut buf)
+            .unwrap();
+        buf
+    }
+
+    #[test]
+    fn test_read_events() {
+        let mut file = encode_record(&step_event(1));
+        file.extend(encode_record(&step_event(2)));
+        let steps: Vec<i64> = EventReader::new(Cursor::new(file))
+            .map(|e| e.unwrap().step)
+            .collect();
+        assert_eq!(steps, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_next_with_crc() {
+        let event = step_event(77);
+        let data = event.encode_to_vec();
+        let mut reader = EventReader::new(Cursor::new(encode_record(&event)));
+        let (got_event, crc) = reader.next_with_crc().unwrap().unwrap();
+        assert_eq!(got_event, event);
+        assert_eq!(crc, MaskedCrc::compute(&data));
+        assert!(reader.next_with_crc().is_none());
+    }
+
+    #[test]
+    fn test_resume_after_truncation() {
+        let record = encode_record(&step_event(5));
+        let (a, b) = record.split_at(record.len() / 2);
+        let mut reader = EventReader::new(ScriptedReader::new(vec![a.to_vec(), b.to_vec()]));
+        assert!(reader.next().is_none());
+        assert_eq!(reader.next().unwrap().unwrap().step, 5);
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn test_repair_aligned_event_file() {
+        let dir = TempDir::create();
+        let path = dir.path().join("events.out.tfevents.test");
+        let mut writer = TensorboardWriter::wrap(Vec::new()).with_alignment(8);
+        for step in 0..5 {
+            writer.write_event(&step_event(step)).unwrap();
+        }
+        let good = writer.into_vec();
+        let record_len = encode_record(&step_event(4)).len();
+        assert_ne!(record_len % 8, 0);
+        let read_steps = || -> Vec<i64> {
+            EventReader::open(&path)
+                .unwrap()
+                .with_alignment(8)
+                .map(|e| e.unwrap().step)
+                .collect()
+        };
+
+        // Already valid: nothing is lost.
+        std::fs::write(&path, &good).unwrap();
+        assert_eq!(
+            repair_aligned_event_file(&path, 8).unwrap(),
+            good.len() as u64
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), good);
+        assert_eq!(read_steps(), vec![0, 1, 2, 3, 4]);
+
+        // A trailing partial record.
+        let mut contents = good.clone();
+        contents.extend(&encode_record(&step_event(5))[..10]);
+        std::fs::write(&path, &contents).unwrap();
+        assert_eq!(
+            repair_aligned_event_file(&path, 8).unwrap(),
+            good.len() as u64
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), good);
+
+        // The last record's padding was cut short, with garbage in its place.
+        let record_end = good.len() - 8 + record_len % 8;
+        let mut contents = good[..record_end].to_vec();
+        contents.push(0xff);
+        std::fs::write(&path, &contents).unwrap();
+        assert_eq!(
+            repair_aligned_event_file(&path, 8).unwrap(),
+            good.len() as u64
+        );
+        assert_eq!(std::fs::read(&path).unwrap(), good);
+        assert_eq!(read_steps(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_aligned_resume_after_truncation() {
+        let mut writer = TensorboardWriter::wrap(Vec::new()).with_alignment(16);
+        writer.write_event(&step_event(1)).unwrap();
+        writer.write_event(&step_event(2)).unwrap();
+        let buf = writer.into_vec();
+        let first_len = encode_record(&step_event(1)).len();
+        assert_ne!(first_len % 16, 0);
+        // Split in the middle of the first record's padding.
+        let (a, b) = buf.split_at(first_len + 1);
+        let mut reader =
+            EventReader::new(ScriptedReader::new(vec![a.to_vec(), b.to_vec()])).with_alignment(16);
+        assert_eq!(reader.next().unwrap().unwrap().step, 1);
+        assert!(reader.next().is_none());
+        assert_eq!(reader.next().unwrap().unwrap().step, 2);
+    }
+
+    #[test]
+    fn test_bad_data_crc() {
+        let record = TfRecord {
+            data: step_event(1).encode_to_vec(),
+            data_crc: MaskedCrc(0x12345678),
+        };
+        let mut buf = Vec::new();
+        record.write(&mut buf).unwrap();
+        let mut reader = EventReader::new(Cursor::new(buf));
+        let err = reader.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_scalar_tag() {
+        let dir = TempDir::create();
+        let path = dir.path().join("events.out.tfevents.test");
+        let mut writer = TensorboardWriter::wrap(File::create(&path).unwrap());
+        writer.write_file_version().unwrap();
+        for step in 0..3 {
+            let summ = SummaryBuilder::new()
+                .scalar("loss", step as f32)
+                .scalar("accuracy", 0.5)
+                .text("loss_notes", &"not a scalar")
+                .build();
+            let wall_time = UNIX_EPOCH + Duration::from_secs(1000 + step as u64);
+            writer.write_summary(wall_time, step, summ).unwrap();
+        }
+        let summ = SummaryBuilder::new().text("loss", &"wrong kind").build();
+        writer.write_summary(UNIX_EPOCH, 9, summ).unwrap();
+        drop(writer);
+
+        assert_eq!(
+            read_scalar_tag(&path, "loss").unwrap(),
+            vec![(0, 1000.0, 0.0), (1, 1001.0, 1.0), (2, 1002.0, 2.0)]
+        );
+        assert_eq!(read_scalar_tag(&path, "accuracy").unwrap().len(), 3);
+        assert_eq!(read_scalar_tag(&path, "nope").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_read_scalar_tag_skims_other_tags() {
+        let dir = TempDir::create();
+        let path = dir.path().join("events.out.tfevents.test");
+        let mut writer = TensorboardWriter::wrap(File::create(&path).unwrap());
+        // A value tagged "other" whose `simple_value` has the wrong wire type, so that the event
+        // can't be fully decoded. Skimming never looks past the tag.
+        let value = b"\x0a\x05other\x10\x01";
+        let mut summary = vec![0x0a, value.len() as u8];
+        summary.extend(value);
+        let mut event = vec![0x2a, summary.len() as u8];
+        event.extend(summary);
+        writer.write_event_bytes(event).unwrap();
+        let summ = SummaryBuilder::new().scalar("loss", 0.5).build();
+        writer.write_summary(UNIX_EPOCH, 1, summ).unwrap();
+        drop(writer);
+
+        assert!(EventReader::open(&path).unwrap().any(|e| e.is_err()));
+        assert_eq!(read_scalar_tag(&path, "loss").unwrap(), vec![(1, 0.0, 0.5)]);
+        assert!(read_scalar_tag(&path, "other").is_err());
+    }
+
+    #[test]
+    fn test_plugins_in_file() {
+        let dir = TempDir::create();
+        let path = dir.path().join("events.out.tfevents.test");
+        let mut writer = TensorboardWriter::wrap(File::create(&path).unwrap());
+        writer.write_file_version().unwrap();
+        let summ = SummaryBuilder::new()
+            .scalar("loss", 0.5)
+            .text("notes", &"hello")
+            .text("more_notes", &"again")
+            .build();
+        writer.write_summary(UNIX_EPOCH, 0, summ).unwrap();
+        drop(writer);
+
+        let plugins = plugins_in_file(&path).unwrap();
+        let expected: HashSet<String> = ["scalars", "text"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(plugins, expected);
+    }
+
+    #[test]
+    fn test_repair_event_file() {
+        let dir = TempDir::create();
+        let path = dir.path().join("events.out.tfevents.test");
+        let mut good = encode_record(&step_event(1));
+        good.extend(encode_record(&step_event(2)));
+
+        // A trailing partial record.
+        let mut contents = good.clone();
+        contents.extend(&encode_record(&step_event(3))[..10]);
+        std::fs::write(&path, &contents).unwrap();
+        assert_eq!(repair_event_file(&path).unwrap(), good.len() as u64);
+        assert_eq!(std::fs::read(&path).unwrap(), good);
+        let steps: Vec<i64> = EventReader::open(&path)
+            .unwrap()
+            .map(|e| e.unwrap().step)
+            .collect();
+        assert_eq!(steps, vec![1, 2]);
+
+        // A trailing record with a corrupt payload.
+        let mut contents = good.clone();
+        let mut bad = encode_record(&step_event(3));
+        bad[14] ^= 0xff;
+        contents.extend(bad);
+        std::fs::write(&path, &contents).unwrap();
+        assert_eq!(repair_event_file(&path).unwrap(), good.len() as u64);
+
+        // Already valid.
+        assert_eq!(repair_event_file(&path).unwrap(), good.len() as u64);
+    }
+
+    #[test]
+    fn test_steps_for_tag() {
+        let dir = TempDir::create();
+        let path = dir.path().join("events.out.tfevents.test");
+        let mut writer = TensorboardWriter::wrap(File::create(&path).unwrap());
+        writer.write_file_version().unwrap();
+        for &(step, tag) in &[
+            (5, "loss"),
+            (0, "loss"),
+            (1, "acc"),
+            (2, "loss"),
+            (5, "loss"),
+        ] {
+            let summ = SummaryBuilder::new().scalar(tag, 1.0).build();
+            writer.write_summary(UNIX_EPOCH, step, summ).unwrap();
+        }
+        drop(writer);
+
+        assert_eq!(steps_for_tag(&path, "loss").unwrap(), vec![0, 2, 5]);
+        assert_eq!(steps_for_tag(&path, "acc").unwrap(), vec![1]);
+        assert_eq!(steps_for_tag(&path, "nope").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn test_lint_event_file() {
+        let dir = TempDir::create();
+        let path = dir.path().join("events.out.tfevents.test");
+        let summary_event = |step, summary| pb::Event {
+            step,
+            what: Some(pb::event::What::Summary(summary)),
+            ..Default::default()
+        };
+        let scalar = |step| summary_event(step, SummaryBuilder::new().scalar("loss", 1.0).build());
+        let raw_tensor = summary_event(
+            3,
+            pb::Summary {
+                value: vec![pb::summary::Value {
+                    tag: "raw".to_string(),
+                    value: Some(pb::summary::value::Value::Tensor(Default::default())),
+                    ..Default::default()
+                }],
+            },
+        );
+        let mut contents = encode_record(&scalar(2));
+        contents.extend(encode_record(&scalar(1)));
+        contents.extend(encode_record(&raw_tensor));
+        contents.extend(encode_record(&step_event(0)));
+        let mut bad = encode_record(&scalar(4));
+        bad[14] ^= 0xff;
+        contents.extend(bad);
+        contents.extend(encode_record(&scalar(5)));
+        contents.extend(&encode_record(&scalar(6))[..10]);
+        std::fs::write(&path, &contents).unwrap();
+        assert_eq!(
+            lint_event_file(&path),
+            vec![
+                LintWarning::MissingFileVersion,
+                LintWarning::NonMonotonicStep {
+                    record: 1,
+                    tag: "loss".to_string(),
+                    step: 1,
+                    previous: 2,
+                },
+                LintWarning::MissingMetadata {
+                    record: 2,
+                    tag: "raw".to_string(),
+                },
+                LintWarning::MisplacedFileVersion { record: 3 },
+                LintWarning::BadChecksum { record: 4 },
+                LintWarning::Truncated,
+            ]
+        );
+
+        let mut writer = TensorboardWriter::wrap(File::create(&path).unwrap());
+        writer.write_file_version().unwrap();
+        for step in 0..3 {
+            let summ = SummaryBuilder::new().scalar("loss", 1.0).build();
+            writer.write_summary(UNIX_EPOCH, step, summ).unwrap();
+        }
+        drop(writer);
+        assert_eq!(lint_event_file(&path), vec![]);
+
+        let missing = lint_event_file(dir.path().join("missing"));
+        assert!(
+            matches!(
+                &missing[..],
+                [LintWarning::Unreadable {
+                    kind: io::ErrorKind::NotFound,
+                    ..
+                }]
+            ),
+            "{:?}",
+            missing
+        );
+    }
+
+    #[test]
+    fn test_scalar_value_tensor() {
+        use pb::summary::value::Value;
+        let tensor = |float_val: Vec<f32>, content: &[u8]| {
+            Value::Tensor(pb::TensorProto {
+                dtype: pb::DataType::DtFloat.into(),
+                float_val,
+                tensor_content: content.to_vec().into(),
+                ..Default::default()
+            })
+        };
+        assert_eq!(scalar_value(&tensor(vec![2.5], b"")), Some(2.5));
+        assert_eq!(
+            scalar_value(&tensor(vec![], &1.5f32.to_le_bytes())),
+            Some(1.5)
+        );
+        assert_eq!(scalar_value(&tensor(vec![1.0, 2.0], b"")), None);
+
+        let summ = SummaryBuilder::new()
+            .scalar_f64("f64", 0.1)
+            .scalar_i64("i64", (1 << 40) + 1)
+            .build();
+        assert_eq!(
+            scalar_value(summ.value[0].value.as_ref().unwrap()),
+            Some(0.1)
+        );
+        assert_eq!(
+            scalar_value(summ.value[1].value.as_ref().unwrap()),
+            Some(((1u64 << 40) + 1) as f64)
+        );
+        let double = Value::Tensor(pb::TensorProto {
+            dtype: pb::DataType::DtDouble.into(),
+            tensor_content: 0.25f64.to_le_bytes().to_vec().into(),
+            ..Default::default()
+        });
+        assert_eq!(scalar_value(&double), Some(0.25));
+    }
+}
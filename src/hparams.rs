@@ -0,0 +1,353 @@
+//! Summaries for TensorBoard's HParams dashboard.
+//!
+//! The HParams dashboard shows a table of runs (*sessions*) with their hyperparameter values and
+//! final metrics, with filtering and a parallel-coordinates view. Each run records its own
+//! hyperparameter values with a *session start* summary, as written by
+//! [`Writer::write_hparams`][crate::TensorboardWriter::write_hparams], and may mark its end with
+//! a *session end* summary. Optionally, an *experiment* summary written by an [`HParamsBuilder`]
+//! declares the hyperparameters and metrics to show; otherwise, TensorBoard infers them from the
+//! sessions.
+//!
+//! ```no_run
+//! use std::time::SystemTime;
+//!
+//! use tensorboard_writer::hparams::{HParamType, HParamValue, HParamsBuilder, Status};
+//! use tensorboard_writer::TensorboardWriter;
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let mut writer = TensorboardWriter::new_initialized("logs/lr=0.01")?;
+//! writer.write_hparams(&[
+//!     ("lr", HParamValue::F64(0.01)),
+//!     ("optimizer", HParamValue::Str("adam".into())),
+//!     ("dropout", HParamValue::Bool(true)),
+//! ])?;
+//! // train...
+//! writer.write_hparams_end(Status::Success)?;
+//!
+//! // Optionally, once per experiment, in the parent directory of the runs:
+//! let mut writer = TensorboardWriter::new_initialized("logs")?;
+//! let experiment = HParamsBuilder::new()
+//!     .hparam_interval("lr", 1e-4, 1e-1)
+//!     .hparam_discrete("optimizer", &[HParamValue::from("adam"), HParamValue::from("sgd")])
+//!     .hparam("dropout", HParamType::Bool)
+//!     .metric("loss")
+//!     .build();
+//! writer.write_summary(SystemTime::now(), 0, experiment)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+
+use crate::proto::google::protobuf as pbs;
+use crate::proto::tensorboard as pb;
+use crate::proto::tensorboard::hparams as hp;
+
+pub use hp::{DataType as HParamType, Status};
+
+const PLUGIN_NAME: &str = "hparams";
+const PLUGIN_DATA_VERSION: i32 = 0;
+
+/// Tag of the summary written by [`HParamsBuilder::build`].
+pub const EXPERIMENT_TAG: &str = "_hparams_/experiment";
+/// Tag of the summary written by [`session_start`].
+pub const SESSION_START_INFO_TAG: &str = "_hparams_/session_start_info";
+/// Tag of the summary written by [`session_end`].
+pub const SESSION_END_INFO_TAG: &str = "_hparams_/session_end_info";
+
+/// The value of a hyperparameter. These are the types that the HParams dashboard supports.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HParamValue {
+    /// A real number. Integer hyperparameters should be converted to this, too.
+    F64(f64),
+    /// A string, like the name of an optimizer.
+    Str(String),
+    /// A boolean flag.
+    Bool(bool),
+}
+
+impl From<f64> for HParamValue {
+    fn from(x: f64) -> Self {
+        HParamValue::F64(x)
+    }
+}
+
+impl From<&str> for HParamValue {
+    fn from(s: &str) -> Self {
+        HParamValue::Str(s.to_string())
+    }
+}
+
+impl From<String> for HParamValue {
+    fn from(s: String) -> Self {
+        HParamValue::Str(s)
+    }
+}
+
+impl From<bool> for HParamValue {
+    fn from(b: bool) -> Self {
+        HParamValue::Bool(b)
+    }
+}
+
+impl HParamValue {
+    fn data_type(&self) -> HParamType {
+        match self {
+            HParamValue::F64(_) => HParamType::Float64,
+            HParamValue::Str(_) => HParamType::String,
+            HParamValue::Bool(_) => HParamType::Bool,
+        }
+    }
+
+    fn to_proto(&self) -> pbs::Value {
+        use pbs::value::Kind;
+        let kind = match self {
+            HParamValue::F64(x) => Kind::NumberValue(*x),
+            HParamValue::Str(s) => Kind::StringValue(s.clone()),
+            HParamValue::Bool(b) => Kind::BoolValue(*b),
+        };
+        pbs::Value { kind: Some(kind) }
+    }
+}
+
+/// Builder for an experiment summary, which declares the hyperparameters and metrics that the
+/// HParams dashboard should show.
+#[derive(Debug, Clone, Default)]
+pub struct HParamsBuilder {
+    experiment: hp::Experiment,
+}
+
+impl HParamsBuilder {
+    /// Creates a builder for an experiment with no hyperparameters or metrics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares a hyperparameter of the given type, with no particular domain.
+    pub fn hparam(self, name: &str, data_type: HParamType) -> Self {
+        self.hparam_info(name, data_type, None)
+    }
+
+    /// Declares a real-valued hyperparameter that takes values in the closed interval
+    /// `[min, max]`.
+    pub fn hparam_interval(self, name: &str, min: f64, max: f64) -> Self {
+        let interval = hp::Interval {
+            min_value: min,
+            max_value: max,
+        };
+        let domain = hp::h_param_info::Domain::DomainInterval(interval);
+        self.hparam_info(name, HParamType::Float64, Some(domain))
+    }
+
+    /// Declares a hyperparameter that takes one of the given values. Its type is taken from the
+    /// first value (or left unset, if there are none).
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the values don't all have the same type.
+    pub fn hparam_discrete(self, name: &str, values: &[HParamValue]) -> Self {
+        let data_type = values.first().map_or(HParamType::Unset, |v| v.data_type());
+        debug_assert!(
+            values.iter().all(|v| v.data_type() == data_type),
+            "hparam {:?} has values of mixed types: {:?}",
+            name,
+            values
+        );
+        let list = pbs::ListValue {
+            values: values.iter().map(HParamValue::to_proto).collect(),
+        };
+        let domain = hp::h_param_info::Domain::DomainDiscrete(list);
+        self.hparam_info(name, data_type, Some(domain))
+    }
+
+    fn hparam_info(
+        mut self,
+        name: &str,
+        data_type: HParamType,
+        domain: Option<hp::h_param_info::Domain>,
+    ) -> Self {
+        self.experiment.hparam_infos.push(hp::HParamInfo {
+            name: name.to_string(),
+            r#type: data_type.into(),
+            domain,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Declares a metric: the scalar time series with the given tag, in every session's run
+    /// directory.
+    pub fn metric(mut self, tag: &str) -> Self {
+        self.experiment.metric_infos.push(hp::MetricInfo {
+            name: Some(hp::MetricName {
+                group: String::new(),
+                tag: tag.to_string(),
+            }),
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Builds the experiment summary. Write it to an event file in the parent directory of the
+    /// runs in the experiment.
+    pub fn build(self) -> pb::Summary {
+        summary(
+            EXPERIMENT_TAG,
+            hp::h_params_plugin_data::Data::Experiment(self.experiment),
+        )
+    }
+}
+
+/// Builds a session start summary, recording a run's hyperparameter values. `start_time_secs`
+/// is the start time of the run, in seconds since the Unix epoch.
+pub fn session_start(hparams: &[(&str, HParamValue)], start_time_secs: f64) -> pb::Summary {
+    let hparams: HashMap<String, pbs::Value> = hparams
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_proto()))
+        .collect();
+    let info = hp::SessionStartInfo {
+        hparams,
+        start_time_secs,
+        ..Default::default()
+    };
+    summary(
+        SESSION_START_INFO_TAG,
+        hp::h_params_plugin_data::Data::SessionStartInfo(info),
+    )
+}
+
+/// Builds a session end summary, marking a run as finished with the given status.
+/// `end_time_secs` is the end time of the run, in seconds since the Unix epoch.
+pub fn session_end(status: Status, end_time_secs: f64) -> pb::Summary {
+    let info = hp::SessionEndInfo {
+        status: status.into(),
+        end_time_secs,
+    };
+    summary(
+        SESSION_END_INFO_TAG,
+        hp::h_params_plugin_data::Data::SessionEndInfo(info),
+    )
+}
+
+/// Builds a summary with a single value that carries `data` in its plugin metadata. The tensor is
+/// just a placeholder, since the plugin reads only the metadata.
+fn summary(tag: &str, data: hp::h_params_plugin_data::Data) -> pb::Summary {
+    let plugin_data = hp::HParamsPluginData {
+        version: PLUGIN_DATA_VERSION,
+        data: Some(data),
+    };
+    let metadata = pb::SummaryMetadata {
+        plugin_data: Some(pb::summary_metadata::PluginData {
+            plugin_name: PLUGIN_NAME.to_string(),
+            content: prost::Message::encode_to_vec(&plugin_data).into(),
+        }),
+        ..Default::default()
+    };
+    let tensor = pb::TensorProto {
+        dtype: pb::DataType::DtFloat.into(),
+        tensor_shape: Some(pb::TensorShapeProto::default()),
+        float_val: vec![0.0],
+        ..Default::default()
+    };
+    pb::Summary {
+        value: vec![pb::summary::Value {
+            tag: tag.to_string(),
+            metadata: Some(metadata),
+            value: Some(pb::summary::value::Value::Tensor(tensor)),
+            ..Default::default()
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    /// Decodes the plugin data from a summary built by this module.
+    fn plugin_data(summary: &pb::Summary) -> (&str, hp::h_params_plugin_data::Data) {
+        let value = &summary.value[0];
+        let plugin_data = value
+            .metadata
+            .as_ref()
+            .unwrap()
+            .plugin_data
+            .as_ref()
+            .unwrap();
+        assert_eq!(plugin_data.plugin_name, "hparams");
+        let data = hp::HParamsPluginData::decode(&plugin_data.content[..]).unwrap();
+        assert_eq!(data.version, 0);
+        (&value.tag, data.data.unwrap())
+    }
+
+    #[test]
+    fn test_session_start() {
+        let summ = session_start(
+            &[
+                ("lr", HParamValue::F64(0.01)),
+                ("optimizer", HParamValue::Str("adam".into())),
+                ("dropout", HParamValue::Bool(false)),
+            ],
+            1234.5,
+        );
+        let info = match plugin_data(&summ) {
+            (SESSION_START_INFO_TAG, hp::h_params_plugin_data::Data::SessionStartInfo(info)) => {
+                info
+            }
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(info.start_time_secs, 1234.5);
+        let kind = |name: &str| info.hparams[name].kind.clone().unwrap();
+        use pbs::value::Kind;
+        assert_eq!(kind("lr"), Kind::NumberValue(0.01));
+        assert_eq!(kind("optimizer"), Kind::StringValue("adam".to_string()));
+        assert_eq!(kind("dropout"), Kind::BoolValue(false));
+    }
+
+    #[test]
+    fn test_session_end() {
+        let summ = session_end(Status::Failure, 99.0);
+        let expected = hp::h_params_plugin_data::Data::SessionEndInfo(hp::SessionEndInfo {
+            status: Status::Failure.into(),
+            end_time_secs: 99.0,
+        });
+        assert_eq!(plugin_data(&summ), (SESSION_END_INFO_TAG, expected));
+    }
+
+    #[test]
+    fn test_experiment() {
+        let summ = HParamsBuilder::new()
+            .hparam_interval("lr", 0.001, 0.1)
+            .hparam_discrete("optimizer", &["adam".into(), "sgd".into()])
+            .hparam("dropout", HParamType::Bool)
+            .metric("loss")
+            .build();
+        let experiment = match plugin_data(&summ) {
+            (EXPERIMENT_TAG, hp::h_params_plugin_data::Data::Experiment(e)) => e,
+            other => panic!("{:?}", other),
+        };
+        let types: Vec<(&str, i32)> = experiment
+            .hparam_infos
+            .iter()
+            .map(|h| (h.name.as_str(), h.r#type))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("lr", HParamType::Float64 as i32),
+                ("optimizer", HParamType::String as i32),
+                ("dropout", HParamType::Bool as i32),
+            ]
+        );
+        match &experiment.hparam_infos[1].domain {
+            Some(hp::h_param_info::Domain::DomainDiscrete(list)) => {
+                assert_eq!(list.values.len(), 2)
+            }
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(
+            experiment.metric_infos[0].name.as_ref().unwrap().tag,
+            "loss"
+        );
+    }
+}
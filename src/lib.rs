@@ -48,6 +48,11 @@
 //!
 //! [protocol buffers]: https://protobuf.dev/
 //!
+//! # Cargo features
+//!
+//! - `image`: Summaries that render images themselves, like
+//!   [`SummaryBuilder::bar_chart`]. These use a small built-in PNG encoder.
+//!
 //! # Examples
 //!
 //! (See also `examples/simple.rs` for a script that you can run.)
@@ -83,20 +88,95 @@
 /// You may need these if you want to drop below the high-level wrappers offered by
 /// [`SummaryBuilder`].
 pub mod proto {
+    #[allow(clippy::large_enum_variant)]
     pub mod tensorboard {
         include!("tensorboard.pb.rs");
+
+        pub mod hparams {
+            include!("tensorboard.hparams.pb.rs");
+        }
+    }
+
+    /// The well-known `google.protobuf` types that TensorBoard protos refer to.
+    pub mod google {
+        pub mod protobuf {
+            include!("google.protobuf.pb.rs");
+        }
     }
 }
 
+mod async_writer;
+mod deflate;
+mod derived_scalar;
 mod masked_crc;
+mod merge;
+mod pbtxt;
+#[cfg(feature = "image")]
+mod png;
+mod profile;
+mod router;
+mod step_timer;
 mod summary;
 mod writer;
 
+pub mod event_reader;
+pub mod hparams;
 pub mod tf_record;
 
+pub use async_writer::AsyncEventWriter;
+pub use derived_scalar::{Derivation, DerivedScalar};
+pub use event_reader::{
+    lint_event_file, plugins_in_file, read_scalar_tag, repair_aligned_event_file,
+    repair_event_file, steps_for_tag, EventReader, LintWarning,
+};
 pub use masked_crc::MaskedCrc;
-pub use summary::SummaryBuilder;
-pub use writer::Writer as TensorboardWriter;
+pub use merge::merge_sorted_into;
+pub use profile::write_profile_trace;
+pub use router::Router;
+pub use step_timer::StepTimer;
+pub use summary::{
+    HistogramAccumulator, LengthMismatch, SummaryBuilder, CONFUSION_MATRIX_PLUGIN_NAME,
+};
+pub use writer::{
+    BuildInfo, FlushPolicy, SessionLogStatus, SharedWriter, SyncAll, SyncPolicy, WriteError,
+    Writer as TensorboardWriter, BUILD_INFO_TAG, CMDLINE_TAG, CUSTOM_SCALARS_TAG, MAX_ALIGNMENT,
+};
+
+/// Something that can write summaries, like a [`TensorboardWriter`].
+///
+/// This lets code that logs summaries be generic over the kind of writer that it's given.
+///
+/// This is a blocking interface: `write_summary` returns once the summary has been written, or
+/// at least queued, as with [`AsyncEventWriter`]. See [`AsyncSummaryWrite`] for the `async`
+/// variant.
+pub trait SummaryWrite {
+    /// Writes a summary, wrapped in an `Event` with the given step and wall time.
+    fn write_summary(
+        &mut self,
+        wall_time: std::time::SystemTime,
+        step: i64,
+        summary: proto::tensorboard::Summary,
+    ) -> std::io::Result<()>;
+}
+
+/// The `async` counterpart of [`SummaryWrite`], for code that logs summaries from async tasks
+/// and should be generic over the kind of writer that it's given.
+///
+/// An [`AsyncEventWriter`] waits for room in its queue without blocking the thread. A
+/// [`TensorboardWriter`] writes the summary right away, before returning an already-completed
+/// future, so it blocks on I/O as usual; it's most useful for tests and simple tools.
+pub trait AsyncSummaryWrite {
+    /// Writes a summary, wrapped in an `Event` with the given step and wall time, resolving once
+    /// it's been written or queued.
+    fn write_summary(
+        &mut self,
+        wall_time: std::time::SystemTime,
+        step: i64,
+        summary: proto::tensorboard::Summary,
+    ) -> impl std::future::Future<Output = std::io::Result<()>> + Send;
+}
 
 #[cfg(test)]
 mod scripted_reader;
+#[cfg(test)]
+mod temp_dir;
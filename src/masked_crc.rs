@@ -44,7 +44,12 @@ const CRC_MASK_DELTA: u32 = 0xa282ead8;
 
 /// Applies a masking permutation to a raw CRC-32C checksum.
 fn mask(crc: u32) -> MaskedCrc {
-    MaskedCrc(((crc >> 15) | (crc << 17)).wrapping_add(CRC_MASK_DELTA))
+    MaskedCrc(crc.rotate_right(15).wrapping_add(CRC_MASK_DELTA))
+}
+
+/// Inverts [`mask`], recovering the raw CRC-32C checksum.
+fn unmask(masked: MaskedCrc) -> u32 {
+    masked.0.wrapping_sub(CRC_MASK_DELTA).rotate_left(15)
 }
 
 impl MaskedCrc {
@@ -63,6 +68,51 @@ impl MaskedCrc {
     pub fn compute(bytes: &[u8]) -> Self {
         mask(crc::crc32::checksum_castagnoli(bytes))
     }
+
+    /// Checks whether this is the checksum of the given data buffer, as when verifying a record
+    /// read from a file.
+    ///
+    /// ```
+    /// use tensorboard_writer::MaskedCrc;
+    ///
+    /// assert!(MaskedCrc(0x5794d08a).verify(b"\x1a\x11CRC test, one two"));
+    /// assert!(!MaskedCrc(0x5794d08a).verify(b"\x1a\x11CRC test, one three"));
+    /// ```
+    pub fn verify(&self, bytes: &[u8]) -> bool {
+        Self::compute(bytes) == *self
+    }
+
+    /// Gets the masked checksum as an integer, as it appears (little-endian) in a TFRecord.
+    ///
+    /// ```
+    /// use tensorboard_writer::MaskedCrc;
+    ///
+    /// assert_eq!(MaskedCrc::compute(b"\x1a\x11CRC test, one two").value(), 0x5794d08a);
+    /// ```
+    pub fn value(&self) -> u32 {
+        self.0
+    }
+
+    /// Masks a raw CRC-32C checksum, as computed by other tools.
+    ///
+    /// The mask rotates the checksum right by 15 bits and then adds `0xa282ead8` (wrapping).
+    ///
+    /// ```
+    /// use tensorboard_writer::MaskedCrc;
+    ///
+    /// let data = b"\x1a\x11CRC test, one two";
+    /// assert_eq!(MaskedCrc::from_raw_crc(0xf2d95a88), MaskedCrc::compute(data));
+    /// ```
+    pub fn from_raw_crc(crc: u32) -> Self {
+        mask(crc)
+    }
+
+    /// Gets the raw CRC-32C checksum that was masked to produce this value, by inverting the mask
+    /// described in [`from_raw_crc`][Self::from_raw_crc]: subtracting `0xa282ead8` (wrapping) and
+    /// then rotating left by 15 bits.
+    pub fn raw_crc(self) -> u32 {
+        unmask(self)
+    }
 }
 
 #[cfg(test)]
@@ -81,6 +131,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_raw_crc() {
+        // Same real TFRecord as in `test_compute`, whose raw CRC-32C is `0xf2d95a88`.
+        assert_eq!(MaskedCrc(0x5794d08a).raw_crc(), 0xf2d95a88);
+        assert_eq!(
+            crc::crc32::checksum_castagnoli(b"\x1a\x11CRC test, one two"),
+            0xf2d95a88
+        );
+        for raw in [0, 1, 0x8a9136aa, 0xffffffff, 0x12345678] {
+            assert_eq!(MaskedCrc::from_raw_crc(raw).raw_crc(), raw);
+        }
+    }
+
     #[test]
     fn test_debug() {
         let long_crc = MaskedCrc(0xf1234567);
@@ -0,0 +1,101 @@
+//! Merging summaries from several sources into one event file.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::{self, Write};
+use std::time::SystemTime;
+
+use crate::proto::tensorboard as pb;
+use crate::writer::Writer;
+
+/// Writes the summaries from all `sources` to `writer`, interleaved in order of step.
+///
+/// Each source should yield `(step, wall_time, summary)` triples in non-decreasing order of step,
+/// as when converting offline logs from several producers. The output is then ordered by step,
+/// which TensorBoard prefers. Records with the same step are written in the order of their
+/// sources, so the merge is stable. If a source isn't sorted, its records are still all written,
+/// but the output won't be sorted either.
+///
+/// ```
+/// use std::time::SystemTime;
+///
+/// use tensorboard_writer::{merge_sorted_into, SummaryBuilder, TensorboardWriter};
+///
+/// let now = SystemTime::now();
+/// let loss = |step, x| (step, now, SummaryBuilder::new().scalar("loss", x).build());
+/// let a = vec![loss(0, 1.0), loss(2, 0.5)];
+/// let b = vec![loss(1, 0.8), loss(3, 0.3)];
+/// let mut writer = TensorboardWriter::wrap(Vec::new());
+/// merge_sorted_into(&mut writer, vec![a.into_iter(), b.into_iter()]).unwrap();
+/// ```
+pub fn merge_sorted_into<W, I>(writer: &mut Writer<W>, sources: Vec<I>) -> io::Result<()>
+where
+    W: Write,
+    I: Iterator<Item = (i64, SystemTime, pb::Summary)>,
+{
+    let mut sources = sources;
+    let mut heads: Vec<Option<(SystemTime, pb::Summary)>> = Vec::with_capacity(sources.len());
+    let mut heap = BinaryHeap::with_capacity(sources.len());
+    for (i, source) in sources.iter_mut().enumerate() {
+        heads.push(source.next().map(|(step, wall_time, summary)| {
+            heap.push(Reverse((step, i)));
+            (wall_time, summary)
+        }));
+    }
+    while let Some(Reverse((step, i))) = heap.pop() {
+        let (wall_time, summary) = heads[i].take().expect("heap entry without a head");
+        writer.write_summary(wall_time, step, summary)?;
+        if let Some((step, wall_time, summary)) = sources[i].next() {
+            heap.push(Reverse((step, i)));
+            heads[i] = Some((wall_time, summary));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventReader, SummaryBuilder};
+
+    #[test]
+    fn test_merge_sorted_into() {
+        let now = SystemTime::now();
+        let source = |tag: &'static str, steps: Vec<i64>| {
+            steps
+                .into_iter()
+                .map(move |step| (step, now, SummaryBuilder::new().scalar(tag, 0.0).build()))
+        };
+        let mut writer = Writer::wrap(Vec::new());
+        let sources = vec![
+            source("a", vec![0, 2, 2, 5]),
+            source("b", vec![1, 2, 3]),
+            source("c", vec![]),
+        ];
+        merge_sorted_into(&mut writer, sources).unwrap();
+        let records: Vec<(i64, String)> = EventReader::new(&writer.into_vec()[..])
+            .map(|event| {
+                let event = event.unwrap();
+                let tag = match event.what {
+                    Some(pb::event::What::Summary(s)) => s.value[0].tag.clone(),
+                    other => panic!("{:?}", other),
+                };
+                (event.step, tag)
+            })
+            .collect();
+        let steps: Vec<i64> = records.iter().map(|(step, _)| *step).collect();
+        assert!(steps.windows(2).all(|w| w[0] <= w[1]), "{:?}", steps);
+        let expected = [
+            (0, "a"),
+            (1, "b"),
+            (2, "a"),
+            (2, "a"),
+            (2, "b"),
+            (3, "b"),
+            (5, "a"),
+        ];
+        let expected: Vec<(i64, String)> =
+            expected.iter().map(|&(s, t)| (s, t.to_string())).collect();
+        assert_eq!(records, expected);
+    }
+}
@@ -0,0 +1,322 @@
+//! Human-readable dumps of events in protobuf text format.
+//!
+//! This is a hand-written formatter for just the messages that can appear in an event, so that we
+//! don't need a reflection library. Like the standard text format, it omits fields with default
+//! values. The output is meant for debugging, not for parsing back.
+
+use std::fmt::{Debug, Display, Write};
+
+use crate::proto::tensorboard as pb;
+
+/// Formats an event in protobuf text format, followed by a newline.
+pub fn format_event(event: &pb::Event) -> String {
+    let mut p = Printer::default();
+    p.event(event);
+    p.out
+}
+
+#[derive(Default)]
+struct Printer {
+    out: String,
+    depth: usize,
+}
+
+impl Printer {
+    fn line(&mut self, args: std::fmt::Arguments) {
+        for _ in 0..self.depth {
+            self.out.push_str("  ");
+        }
+        self.out.write_fmt(args).unwrap();
+        self.out.push('\n');
+    }
+
+    fn scalar<T: Display + Default + PartialEq>(&mut self, name: &str, value: T) {
+        if value != T::default() {
+            self.line(format_args!("{}: {}", name, value));
+        }
+    }
+
+    fn float(&mut self, name: &str, value: f64) {
+        if value != 0.0 {
+            self.line(format_args!("{}: {}", name, FloatText(value)));
+        }
+    }
+
+    fn bytes(&mut self, name: &str, value: &[u8]) {
+        if !value.is_empty() {
+            self.line(format_args!("{}: \"{}\"", name, escape(value)));
+        }
+    }
+
+    fn enumeration<E: Debug>(&mut self, name: &str, value: i32, prefix: &str, e: Option<E>) {
+        if value == 0 {
+            return;
+        }
+        match e {
+            Some(e) => {
+                let name_str = screaming_snake(&format!("{:?}", e));
+                self.line(format_args!("{}: {}{}", name, prefix, name_str));
+            }
+            None => self.line(format_args!("{}: {}", name, value)),
+        }
+    }
+
+    fn message(&mut self, name: &str, f: impl FnOnce(&mut Self)) {
+        self.line(format_args!("{} {{", name));
+        self.depth += 1;
+        f(self);
+        self.depth -= 1;
+        self.line(format_args!("}}"));
+    }
+
+    fn event(&mut self, event: &pb::Event) {
+        use pb::event::What;
+        self.float("wall_time", event.wall_time);
+        self.scalar("step", event.step);
+        match &event.what {
+            None => (),
+            Some(What::FileVersion(v)) => self.bytes("file_version", v.as_bytes()),
+            Some(What::GraphDef(v)) => self.bytes("graph_def", v),
+            Some(What::Summary(s)) => self.message("summary", |p| p.summary(s)),
+            Some(What::LogMessage(m)) => self.message("log_message", |p| {
+                let level = pb::log_message::Level::from_i32(m.level);
+                p.enumeration("level", m.level, "", level);
+                p.bytes("message", m.message.as_bytes());
+            }),
+            Some(What::SessionLog(s)) => self.message("session_log", |p| {
+                let status = pb::session_log::SessionStatus::from_i32(s.status);
+                p.enumeration("status", s.status, "", status);
+                p.bytes("checkpoint_path", s.checkpoint_path.as_bytes());
+                p.bytes("msg", s.msg.as_bytes());
+            }),
+            Some(What::TaggedRunMetadata(m)) => self.message("tagged_run_metadata", |p| {
+                p.bytes("tag", m.tag.as_bytes());
+                p.bytes("run_metadata", &m.run_metadata);
+            }),
+            Some(What::MetaGraphDef(v)) => self.bytes("meta_graph_def", v),
+        }
+        if let Some(m) = &event.source_metadata {
+            self.message("source_metadata", |p| {
+                p.bytes("writer", m.writer.as_bytes())
+            });
+        }
+    }
+
+    fn summary(&mut self, summary: &pb::Summary) {
+        for value in &summary.value {
+            self.message("value", |p| p.summary_value(value));
+        }
+    }
+
+    fn summary_value(&mut self, value: &pb::summary::Value) {
+        use pb::summary::value::Value;
+        self.bytes("node_name", value.node_name.as_bytes());
+        self.bytes("tag", value.tag.as_bytes());
+        if let Some(m) = &value.metadata {
+            self.message("metadata", |p| p.summary_metadata(m));
+        }
+        match &value.value {
+            None => (),
+            Some(Value::SimpleValue(x)) => {
+                self.line(format_args!("simple_value: {}", FloatText(f64::from(*x))))
+            }
+            Some(Value::ObsoleteOldStyleHistogram(v)) => {
+                self.bytes("obsolete_old_style_histogram", v)
+            }
+            Some(Value::Image(image)) => self.message("image", |p| {
+                p.scalar("height", image.height);
+                p.scalar("width", image.width);
+                p.scalar("colorspace", image.colorspace);
+                p.bytes("encoded_image_string", &image.encoded_image_string);
+            }),
+            Some(Value::Histo(h)) => self.message("histo", |p| p.histogram(h)),
+            Some(Value::Audio(audio)) => self.message("audio", |p| {
+                p.float("sample_rate", f64::from(audio.sample_rate));
+                p.scalar("num_channels", audio.num_channels);
+                p.scalar("length_frames", audio.length_frames);
+                p.bytes("encoded_audio_string", &audio.encoded_audio_string);
+                p.bytes("content_type", audio.content_type.as_bytes());
+            }),
+            Some(Value::Tensor(t)) => self.message("tensor", |p| p.tensor(t)),
+        }
+    }
+
+    fn summary_metadata(&mut self, meta: &pb::SummaryMetadata) {
+        if let Some(data) = &meta.plugin_data {
+            self.message("plugin_data", |p| {
+                p.bytes("plugin_name", data.plugin_name.as_bytes());
+                p.bytes("content", &data.content);
+            });
+        }
+        self.bytes("display_name", meta.display_name.as_bytes());
+        self.bytes("summary_description", meta.summary_description.as_bytes());
+        let data_class = pb::DataClass::from_i32(meta.data_class);
+        self.enumeration("data_class", meta.data_class, "DATA_CLASS_", data_class);
+    }
+
+    fn histogram(&mut self, h: &pb::HistogramProto) {
+        self.float("min", h.min);
+        self.float("max", h.max);
+        self.float("num", h.num);
+        self.float("sum", h.sum);
+        self.float("sum_squares", h.sum_squares);
+        for &limit in &h.bucket_limit {
+            self.line(format_args!("bucket_limit: {}", FloatText(limit)));
+        }
+        for &count in &h.bucket {
+            self.line(format_args!("bucket: {}", FloatText(count)));
+        }
+    }
+
+    fn tensor(&mut self, t: &pb::TensorProto) {
+        let dtype = pb::DataType::from_i32(t.dtype);
+        self.enumeration("dtype", t.dtype, "", dtype);
+        if let Some(shape) = &t.tensor_shape {
+            self.message("tensor_shape", |p| {
+                for dim in &shape.dim {
+                    p.message("dim", |p| {
+                        p.scalar("size", dim.size);
+                        p.bytes("name", dim.name.as_bytes());
+                    });
+                }
+                p.scalar("unknown_rank", shape.unknown_rank);
+            });
+        }
+        self.scalar("version_number", t.version_number);
+        self.bytes("tensor_content", &t.tensor_content);
+        for &x in &t.half_val {
+            self.line(format_args!("half_val: {}", x));
+        }
+        for &x in &t.float_val {
+            self.line(format_args!("float_val: {}", FloatText(f64::from(x))));
+        }
+        for &x in &t.double_val {
+            self.line(format_args!("double_val: {}", FloatText(x)));
+        }
+        for &x in &t.int_val {
+            self.line(format_args!("int_val: {}", x));
+        }
+        for x in &t.string_val {
+            self.line(format_args!("string_val: \"{}\"", escape(x)));
+        }
+        for &x in &t.int64_val {
+            self.line(format_args!("int64_val: {}", x));
+        }
+        for &x in &t.bool_val {
+            self.line(format_args!("bool_val: {}", x));
+        }
+        for &x in &t.uint32_val {
+            self.line(format_args!("uint32_val: {}", x));
+        }
+        for &x in &t.uint64_val {
+            self.line(format_args!("uint64_val: {}", x));
+        }
+    }
+}
+
+/// Formats a float the way the protobuf text format does, e.g., `inf` and `nan`.
+struct FloatText(f64);
+
+impl Display for FloatText {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            x if x.is_nan() => f.write_str("nan"),
+            x if x == f64::INFINITY => f.write_str("inf"),
+            x if x == f64::NEG_INFINITY => f.write_str("-inf"),
+            x => write!(f, "{}", x),
+        }
+    }
+}
+
+/// Escapes a byte string for a quoted text format literal, using C-style escapes for quotes,
+/// backslashes, and non-printable bytes.
+fn escape(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len());
+    for &b in bytes {
+        match b {
+            b'"' => out.push_str("\\\""),
+            b'\\' => out.push_str("\\\\"),
+            b'\n' => out.push_str("\\n"),
+            b'\r' => out.push_str("\\r"),
+            b'\t' => out.push_str("\\t"),
+            0x20..=0x7e => out.push(b as char),
+            _ => write!(out, "\\{:03o}", b).unwrap(),
+        }
+    }
+    out
+}
+
+/// Converts a `CamelCase` enum variant name, as from `Debug`, to `SCREAMING_SNAKE_CASE`, as in
+/// the `.proto` file.
+fn screaming_snake(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            out.push('_');
+        }
+        out.push(c.to_ascii_uppercase());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SummaryBuilder;
+
+    #[test]
+    fn test_format_event() {
+        let event = pb::Event {
+            wall_time: 1.5,
+            step: 2,
+            what: Some(pb::event::What::Summary(
+                SummaryBuilder::new()
+                    .scalar("loss", 0.25)
+                    .text("notes", &"say \"hi\"\n")
+                    .build(),
+            )),
+            ..Default::default()
+        };
+        let expected = r#"wall_time: 1.5
+step: 2
+summary {
+  value {
+    tag: "loss"
+    simple_value: 0.25
+  }
+  value {
+    tag: "notes"
+    metadata {
+      plugin_data {
+        plugin_name: "text"
+      }
+    }
+    tensor {
+      dtype: DT_STRING
+      tensor_shape {
+      }
+      string_val: "say \"hi\"\n"
+    }
+  }
+}
+"#;
+        assert_eq!(format_event(&event), expected);
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(b"a\\b\x00\xff"), "a\\\\b\\000\\377");
+    }
+
+    #[test]
+    fn test_enums() {
+        let meta = pb::SummaryMetadata {
+            data_class: pb::DataClass::BlobSequence.into(),
+            ..Default::default()
+        };
+        let mut p = Printer::default();
+        p.summary_metadata(&meta);
+        assert_eq!(p.out, "data_class: DATA_CLASS_BLOB_SEQUENCE\n");
+        assert_eq!(FloatText(f64::NEG_INFINITY).to_string(), "-inf");
+    }
+}
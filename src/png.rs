@@ -0,0 +1,103 @@
+//! Minimal PNG encoder, just enough to log generated images without an image library.
+//!
+//! Image data is stored uncompressed (using "stored" deflate blocks), which is perfectly valid PNG
+//! and keeps this encoder tiny. The images we generate are small and mostly flat color, so the
+//! size overhead doesn't matter much.
+
+use crate::deflate;
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Encodes 8-bit RGB pixel data, in row-major order, as a PNG image.
+///
+/// # Panics
+///
+/// Panics if `pixels.len()` is not `width * height * 3`.
+pub fn encode_rgb(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+    let row_len = width as usize * 3;
+    assert_eq!(
+        pixels.len(),
+        row_len * height as usize,
+        "pixel buffer does not match image dimensions"
+    );
+
+    // Each scanline is prefixed with a filter type byte; 0 means "no filter".
+    let mut raw = Vec::with_capacity((row_len + 1) * height as usize);
+    for row in pixels.chunks(row_len.max(1)).take(height as usize) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    let mut ihdr = Vec::with_capacity(13);
+    ihdr.extend(width.to_be_bytes());
+    ihdr.extend(height.to_be_bytes());
+    // bit depth 8, color type 2 (RGB), compression 0, filter 0, interlace 0
+    ihdr.extend([8, 2, 0, 0, 0]);
+
+    let mut out = SIGNATURE.to_vec();
+    write_chunk(&mut out, b"IHDR", &ihdr);
+    write_chunk(&mut out, b"IDAT", &zlib_stored(&raw));
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+/// Writes a PNG chunk: length, type, data, and a CRC-32 of the type and data.
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(data);
+    let crc = crc::crc32::checksum_ieee(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01]; // deflate, 32K window, no preset dictionary
+    deflate::write_stored(&mut out, data);
+    out.extend(adler32(data).to_be_bytes());
+    out
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % MOD;
+        b = (b + a) % MOD;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deflate::MAX_STORED_BLOCK;
+
+    #[test]
+    fn test_adler32() {
+        // Example from Wikipedia.
+        assert_eq!(adler32(b"Wikipedia"), 0x11e60398);
+        assert_eq!(adler32(b""), 1);
+    }
+
+    #[test]
+    fn test_encode_rgb() {
+        let png = encode_rgb(2, 1, &[255, 0, 0, 0, 0, 255]);
+        assert!(png.starts_with(SIGNATURE));
+        // IHDR chunk immediately follows the signature.
+        assert_eq!(&png[8..16], b"\x00\x00\x00\x0dIHDR");
+        assert_eq!(&png[16..24], b"\x00\x00\x00\x02\x00\x00\x00\x01");
+        assert!(png.ends_with(b"\x00\x00\x00\x00IEND\xae\x42\x60\x82"));
+    }
+
+    #[test]
+    fn test_zlib_stored_multiple_blocks() {
+        let data = vec![7u8; MAX_STORED_BLOCK + 10];
+        let z = zlib_stored(&data);
+        // header, two block headers, data, and checksum
+        assert_eq!(z.len(), 2 + 2 * 5 + data.len() + 4);
+        assert_eq!(z[2], 0x00); // first block is not final
+        assert_eq!(z[2 + 5 + MAX_STORED_BLOCK], 0x01); // second block is final
+    }
+}
@@ -0,0 +1,84 @@
+//! Trace files for TensorBoard's Profile dashboard.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::deflate;
+use crate::writer::civil_from_days;
+
+/// Writes a trace in the [Chrome trace event format][format] where the Profile dashboard will find
+/// it, returning the path of the new file.
+///
+/// The profile plugin looks for *profiling sessions* under `plugins/profile/` in each run
+/// directory. Each session is a subdirectory named for the time that it was captured, as
+/// `YYYY_MM_DD_HH_MM_SS` in UTC, and contains one gzipped trace per host, named
+/// `<hostname>.trace.json.gz`. So this writes `trace_json` to, e.g.,
+/// `run_dir/plugins/profile/2023_01_01_14_30_00/myhost.trace.json.gz`, creating directories as
+/// needed. A trace already there for the same session and host is replaced.
+///
+/// [format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+///
+/// ```no_run
+/// use std::time::SystemTime;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let trace = br#"{"traceEvents": [{"name": "step", "ph": "X", "ts": 0, "dur": 1000}]}"#;
+/// tensorboard_writer::write_profile_trace("logs/train", SystemTime::now(), trace)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn write_profile_trace<P: AsRef<Path>>(
+    run_dir: P,
+    run_timestamp: SystemTime,
+    trace_json: &[u8],
+) -> io::Result<PathBuf> {
+    let session_dir = run_dir
+        .as_ref()
+        .join("plugins")
+        .join("profile")
+        .join(session_name(run_timestamp));
+    fs::create_dir_all(&session_dir)?;
+    let mut file_name = hostname::get().unwrap_or_else(|_| "localhost".into());
+    file_name.push(".trace.json.gz");
+    let path = session_dir.join(file_name);
+    fs::write(&path, deflate::gzip_stored(trace_json))?;
+    Ok(path)
+}
+
+/// Formats a time (in UTC) as the name of a profiling session directory, like
+/// `2023_01_01_14_30_00`.
+fn session_name(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |dt| dt.as_secs());
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let secs_of_day = secs % 86400;
+    format!(
+        "{year:04}_{month:02}_{day:02}_{:02}_{:02}_{:02}",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_dir::TempDir;
+    use std::time::Duration;
+
+    #[test]
+    fn test_write_profile_trace() {
+        let dir = TempDir::create();
+        let time = std::time::UNIX_EPOCH + Duration::from_secs(1672583400);
+        let path = write_profile_trace(dir.path().join("run"), time, b"{}").unwrap();
+        let session_dir = dir.path().join("run/plugins/profile/2023_01_01_14_30_00");
+        assert_eq!(path.parent(), Some(session_dir.as_path()));
+        let file_name = path.file_name().unwrap().to_str().unwrap();
+        assert!(file_name.ends_with(".trace.json.gz"), "{}", file_name);
+        let contents = fs::read(&path).unwrap();
+        assert_eq!(contents, deflate::gzip_stored(b"{}"));
+    }
+}
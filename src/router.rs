@@ -0,0 +1,129 @@
+//! Fan-out of summaries from one producer to many runs.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::proto::tensorboard as pb;
+use crate::writer::Writer;
+
+/// Routes summaries to per-run event files under a common base directory.
+///
+/// Each run gets its own [`Writer`], which is created the first time that run is written to: the
+/// run directory is `base_dir.join(run)`, so a run name like `train/eval` becomes a nested
+/// directory. New writers start with a file version event.
+///
+/// ```no_run
+/// use std::time::SystemTime;
+///
+/// use tensorboard_writer::{Router, SummaryBuilder};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut router = Router::new("my_model");
+/// for step in 0..10 {
+///     let now = SystemTime::now();
+///     router.write("train", now, step, SummaryBuilder::new().scalar("loss", 0.5).build())?;
+///     router.write("eval", now, step, SummaryBuilder::new().scalar("loss", 0.7).build())?;
+///     router.flush()?;
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct Router {
+    base_dir: PathBuf,
+    writers: HashMap<String, Writer<BufWriter<File>>>,
+}
+
+impl Router {
+    /// Creates a router that writes runs under the given base directory. No files or directories
+    /// are created until the first write.
+    pub fn new<P: AsRef<Path>>(base_dir: P) -> Self {
+        Router {
+            base_dir: base_dir.as_ref().to_path_buf(),
+            writers: HashMap::new(),
+        }
+    }
+
+    /// Gets the writer for the given run, creating it (and writing its file version) if needed.
+    pub fn writer(&mut self, run: &str) -> io::Result<&mut Writer<BufWriter<File>>> {
+        if !self.writers.contains_key(run) {
+            let mut writer = Writer::new(self.base_dir.join(run))?;
+            writer.write_file_version()?;
+            self.writers.insert(run.to_string(), writer);
+        }
+        Ok(self.writers.get_mut(run).unwrap())
+    }
+
+    /// Writes a summary to the given run; see [`Writer::write_summary`].
+    pub fn write(
+        &mut self,
+        run: &str,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> io::Result<()> {
+        Ok(self.writer(run)?.write_summary(wall_time, step, summary)?)
+    }
+
+    /// Flushes the writers for all runs that have been written to so far.
+    pub fn flush(&mut self) -> io::Result<()> {
+        for writer in self.writers.values_mut() {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_reader::EventReader;
+    use crate::temp_dir::TempDir;
+    use crate::SummaryBuilder;
+
+    /// Reads `(step, tag, simple_value)` triples from the sole event file in `run_dir`.
+    fn read_scalars(run_dir: &Path) -> Vec<(i64, String, f32)> {
+        let files: Vec<_> = std::fs::read_dir(run_dir)
+            .unwrap()
+            .map(|e| e.unwrap().path())
+            .collect();
+        assert_eq!(files.len(), 1, "{:?}", files);
+        let mut result = Vec::new();
+        for event in EventReader::open(&files[0]).unwrap() {
+            let event = event.unwrap();
+            if let Some(pb::event::What::Summary(summ)) = event.what {
+                for v in summ.value {
+                    if let Some(pb::summary::value::Value::SimpleValue(x)) = v.value {
+                        result.push((event.step, v.tag, x));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_router() {
+        let dir = TempDir::create();
+        let mut router = Router::new(dir.path());
+        let now = SystemTime::now();
+        for step in 0..2 {
+            let loss = |x: f32| SummaryBuilder::new().scalar("loss", x).build();
+            router.write("train", now, step, loss(1.0)).unwrap();
+            router.write("eval", now, step, loss(2.0)).unwrap();
+        }
+        router.flush().unwrap();
+
+        let loss = |step, x| (step, "loss".to_string(), x);
+        assert_eq!(
+            read_scalars(&dir.path().join("train")),
+            vec![loss(0, 1.0), loss(1, 1.0)]
+        );
+        assert_eq!(
+            read_scalars(&dir.path().join("eval")),
+            vec![loss(0, 2.0), loss(1, 2.0)]
+        );
+    }
+}
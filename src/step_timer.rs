@@ -0,0 +1,138 @@
+//! Per-step timing metrics.
+
+use std::time::{Duration, Instant};
+
+use crate::summary::SummaryBuilder;
+
+/// Stopwatch for logging how long each training step takes.
+///
+/// Call [`start`][Self::start] at the beginning of a step and [`stop`][Self::stop] at the end.
+/// Stopping adds scalars to a [`SummaryBuilder`], under the given tag prefix:
+///
+/// - `{tag}/seconds`: how long this step took;
+/// - `{tag}/steps_per_sec`: the reciprocal, i.e., throughput in steps per second;
+/// - `{tag}/avg_seconds`: the mean duration of all steps timed so far.
+///
+/// ```
+/// use tensorboard_writer::{StepTimer, SummaryBuilder};
+///
+/// let mut timer = StepTimer::new();
+/// for _ in 0..3 {
+///     timer.start();
+///     // train...
+///     let summ = timer.stop(SummaryBuilder::new(), "step_time").build();
+///     assert_eq!(summ.value.len(), 3);
+/// }
+/// ```
+pub struct StepTimer<C = fn() -> Instant> {
+    clock: C,
+    started: Option<Instant>,
+    total: Duration,
+    steps: u32,
+}
+
+impl StepTimer {
+    /// Creates a timer that reads the system's monotonic clock.
+    pub fn new() -> Self {
+        Self::with_clock(Instant::now)
+    }
+}
+
+impl Default for StepTimer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: FnMut() -> Instant> StepTimer<C> {
+    /// Creates a timer that reads the time from the given clock function. This is mostly useful
+    /// for testing.
+    pub fn with_clock(clock: C) -> Self {
+        StepTimer {
+            clock,
+            started: None,
+            total: Duration::ZERO,
+            steps: 0,
+        }
+    }
+
+    /// Starts timing a step, discarding any step that was started but not stopped.
+    pub fn start(&mut self) {
+        self.started = Some((self.clock)());
+    }
+
+    /// Stops timing the current step and adds its timing scalars to `builder`, returning the
+    /// updated builder. If no step was started, the builder is returned unchanged.
+    pub fn stop(&mut self, builder: SummaryBuilder, tag: &str) -> SummaryBuilder {
+        let started = match self.started.take() {
+            Some(t) => t,
+            None => return builder,
+        };
+        let elapsed = (self.clock)().saturating_duration_since(started);
+        self.total += elapsed;
+        self.steps += 1;
+        let secs = elapsed.as_secs_f64();
+        let avg_secs = self.total.as_secs_f64() / f64::from(self.steps);
+        builder
+            .scalar(&format!("{}/seconds", tag), secs as f32)
+            .scalar(&format!("{}/steps_per_sec", tag), (1.0 / secs) as f32)
+            .scalar(&format!("{}/avg_seconds", tag), avg_secs as f32)
+    }
+
+    /// The number of steps that have been timed so far.
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::tensorboard::summary::value::Value as InnerValue;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_step_timer() {
+        let base = Instant::now();
+        let now = Rc::new(Cell::new(base));
+        let clock = {
+            let now = Rc::clone(&now);
+            move || now.get()
+        };
+        let mut timer = StepTimer::with_clock(clock);
+
+        let mut results = Vec::new();
+        for millis in [250, 750] {
+            timer.start();
+            now.set(now.get() + Duration::from_millis(millis));
+            let summ = timer.stop(SummaryBuilder::new(), "step").build();
+            let values: Vec<(String, f32)> = summ
+                .value
+                .into_iter()
+                .map(|v| match v.value {
+                    Some(InnerValue::SimpleValue(x)) => (v.tag, x),
+                    other => panic!("{:?}", other),
+                })
+                .collect();
+            results.push(values);
+        }
+        let expected = |secs: f32, avg: f32| {
+            vec![
+                ("step/seconds".to_string(), secs),
+                ("step/steps_per_sec".to_string(), 1.0 / secs),
+                ("step/avg_seconds".to_string(), avg),
+            ]
+        };
+        assert_eq!(results, vec![expected(0.25, 0.25), expected(0.75, 0.5)]);
+        assert_eq!(timer.steps(), 2);
+    }
+
+    #[test]
+    fn test_stop_without_start() {
+        let mut timer = StepTimer::new();
+        let summ = timer.stop(SummaryBuilder::new(), "step").build();
+        assert!(summ.value.is_empty());
+        assert_eq!(timer.steps(), 0);
+    }
+}
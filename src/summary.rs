@@ -1,6 +1,21 @@
 use super::proto::tensorboard as pb;
 use pb::summary::value::Value as InnerValue;
 
+/// Error returned when the inputs to a [`SummaryBuilder`] method have inconsistent lengths.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("length mismatch: got {got} {what}, want {want}")]
+pub struct LengthMismatch {
+    /// What was being counted, like `"counts"`.
+    pub what: &'static str,
+    /// The actual number of items.
+    pub got: usize,
+    /// The number of items required to match the other inputs.
+    pub want: usize,
+}
+
+/// The plugin name in the metadata of [`SummaryBuilder::confusion_tensor`] values.
+pub const CONFUSION_MATRIX_PLUGIN_NAME: &str = "confusion_matrix";
+
 /// Builder for constructing TensorBoard `Summary` protocol buffers.
 ///
 /// To use this builder, construct an instance with [`new`][Self::new], chain builder methods like
@@ -19,9 +34,16 @@ use pb::summary::value::Value as InnerValue;
 ///
 /// For more precise control over the values created, you can use the [`value`][Self::value]
 /// builder to pass a raw TensorBoard `Summary.Value` protobuf that you've prepared ahead of time.
+///
+/// The builder doesn't deduplicate tags: adding two values with the same tag yields a summary
+/// with both. TensorBoard's behavior on duplicate tags within one event is undefined, so to
+/// replace a value, [`remove`][Self::remove] its tag first.
 #[derive(Default)]
 pub struct SummaryBuilder {
     summary: pb::Summary,
+    /// Values from [`value_raw`][Self::value_raw], as tags and encoded `Summary.Value`s, kept
+    /// verbatim so that fields unknown to this crate survive.
+    raw_values: Vec<(String, Vec<u8>)>,
 }
 
 impl SummaryBuilder {
@@ -31,8 +53,32 @@ impl SummaryBuilder {
     }
 
     /// Finishes this builder and returns the summary that's been constructed.
+    ///
+    /// Values added with [`value_raw`][Self::value_raw] are decoded into this crate's protos,
+    /// which drops any fields that they don't know about, or the whole value if it isn't a valid
+    /// encoding. Use [`build_encoded`][Self::build_encoded] to keep them intact.
     pub fn build(self) -> pb::Summary {
-        self.summary
+        use prost::Message;
+        let mut summary = self.summary;
+        let raw = self.raw_values.iter();
+        summary
+            .value
+            .extend(raw.filter_map(|(_, v)| pb::summary::Value::decode(&v[..]).ok()));
+        summary
+    }
+
+    /// Finishes this builder and returns the wire encoding of the summary that's been
+    /// constructed, for [`Writer::write_summary_encoded`][crate::Writer::write_summary_encoded].
+    ///
+    /// Unlike [`build`][Self::build], this keeps values added with [`value_raw`][Self::value_raw]
+    /// byte for byte, after all the other values.
+    pub fn build_encoded(self) -> Vec<u8> {
+        use prost::Message;
+        let mut buf = self.summary.encode_to_vec();
+        for (_, value) in &self.raw_values {
+            encode_length_delimited(1, value, &mut buf);
+        }
+        buf
     }
 
     /// Adds an arbitrary [`tensorboard.Summary.Value`][pb::summary::Value] protobuf value.
@@ -41,12 +87,93 @@ impl SummaryBuilder {
         self
     }
 
+    /// Adds all the values of another summary, after the values added so far. This combines
+    /// summaries built by independent parts of a program into one, to write as a single event.
+    ///
+    /// Values are copied as is, so if both summaries have a value with the same tag, the result
+    /// will too.
+    pub fn extend(mut self, other: pb::Summary) -> Self {
+        self.summary.value.extend(other.value);
+        self
+    }
+
+    /// Removes all the values added so far with the given tag, so that a value added after this
+    /// replaces them:
+    ///
+    /// ```
+    /// use tensorboard_writer::SummaryBuilder;
+    ///
+    /// let summ = SummaryBuilder::new()
+    ///     .scalar("loss", 1.0)
+    ///     .remove("loss")
+    ///     .scalar("loss", 0.5)
+    ///     .build();
+    /// assert_eq!(summ.value.len(), 1);
+    /// ```
+    pub fn remove(mut self, tag: &str) -> Self {
+        self.summary.value.retain(|v| v.tag != tag);
+        self.raw_values.retain(|(t, _)| t != tag);
+        self
+    }
+
+    /// Sets the display name and Markdown description in the metadata of the value added last,
+    /// keeping any plugin data it already has. This works with any kind of value:
+    ///
+    /// ```
+    /// use tensorboard_writer::SummaryBuilder;
+    ///
+    /// let summ = SummaryBuilder::new()
+    ///     .histogram("weights", 30, &[0.1, 0.2, 0.3])
+    ///     .describe("Layer 1 weights", "Before the *first* ReLU.")
+    ///     .build();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if no value has been added yet.
+    pub fn describe(mut self, display_name: &str, description: &str) -> Self {
+        debug_assert!(
+            !self.summary.value.is_empty(),
+            "describe called before adding a value"
+        );
+        if let Some(value) = self.summary.value.last_mut() {
+            let meta = value.metadata.get_or_insert_with(Default::default);
+            meta.display_name = display_name.to_string();
+            meta.summary_description = description.to_string();
+        }
+        self
+    }
+
+    /// Adds a value from the wire encoding of a `tensorboard.Summary.Value` protobuf, with the
+    /// given tag.
+    ///
+    /// This is useful for values that were serialized by some other tool, including kinds of
+    /// values that this crate's protos don't know about yet. The bytes are kept verbatim: the tag
+    /// is encoded in front of them, and [`build_encoded`][Self::build_encoded] writes the result
+    /// as a value of the summary, after all the other values. The bytes aren't checked, and they
+    /// shouldn't set a tag of their own, since readers would take that one instead.
+    ///
+    /// [`build`][Self::build] can only keep the parts of these values that this crate knows
+    /// about, and [`describe`][Self::describe] doesn't apply to them.
+    pub fn value_raw(mut self, tag: &str, serialized_value: &[u8]) -> Self {
+        use prost::Message;
+        let mut value = pb::summary::Value {
+            tag: tag.to_string(),
+            ..Default::default()
+        }
+        .encode_to_vec();
+        value.extend_from_slice(serialized_value);
+        self.raw_values.push((tag.to_string(), value));
+        self
+    }
+
     fn build_value(self, tag: &str, inner: InnerValue, meta: Option<pb::SummaryMetadata>) -> Self {
-        let mut outer = pb::summary::Value::default();
-        outer.tag = tag.to_string();
-        outer.value = Some(inner);
-        outer.metadata = meta;
-        self.value(outer)
+        self.value(pb::summary::Value {
+            tag: tag.to_string(),
+            value: Some(inner),
+            metadata: meta,
+            ..Default::default()
+        })
     }
 
     /// Adds a scalar summary.
@@ -54,11 +181,95 @@ impl SummaryBuilder {
         self.build_value(tag, InnerValue::SimpleValue(scalar), None)
     }
 
+    /// Adds a scalar summary with a display name and a Markdown description, which TensorBoard
+    /// shows alongside the chart. TensorBoard keeps the metadata from the first value it reads for
+    /// each tag, so it's enough to describe a scalar once (e.g., at step 0); see
+    /// [`describe`][Self::describe].
+    pub fn scalar_described(
+        self,
+        tag: &str,
+        value: f32,
+        display_name: &str,
+        description: &str,
+    ) -> Self {
+        self.scalar(tag, value).describe(display_name, description)
+    }
+
+    /// Adds a scalar summary stored as a rank-0 `float64` tensor, for values that need more
+    /// precision than [`scalar`][Self::scalar] keeps (e.g., a loss that changes by tiny amounts
+    /// late in training). The scalars dashboard reads these just like `f32` scalars.
+    pub fn scalar_f64(self, tag: &str, value: f64) -> Self {
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtDouble.into(),
+            tensor_shape: Some(tensor_shape(&[])),
+            double_val: vec![value],
+            ..Default::default()
+        };
+        self.build_value(tag, InnerValue::Tensor(tensor), Some(scalar_metadata()))
+    }
+
+    /// Adds a scalar summary stored as a rank-0 `int64` tensor, for integer counters (like
+    /// examples seen) that [`scalar`][Self::scalar] can't represent exactly past `2^24`. The
+    /// scalars dashboard charts these like any other scalar.
+    pub fn scalar_i64(self, tag: &str, value: i64) -> Self {
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtInt64.into(),
+            tensor_shape: Some(tensor_shape(&[])),
+            int64_val: vec![value],
+            ..Default::default()
+        };
+        self.build_value(tag, InnerValue::Tensor(tensor), Some(scalar_metadata()))
+    }
+
+    /// Adds a scalar summary along with a text note about it, under the tag `{tag}/notes`.
+    ///
+    /// TensorBoard can't annotate points on a chart, but the note shows up in the text dashboard
+    /// at the same step, which is handy for marking things like "learning rate dropped here".
+    pub fn scalar_annotated(self, tag: &str, scalar: f32, note: &str) -> Self {
+        self.scalar(tag, scalar)
+            .text(&format!("{}/notes", tag), note)
+    }
+
+    /// Adds scalar summaries for debugging exploding gradients, given the L2 norm of each
+    /// parameter's gradient and a clipping threshold, as for clipping by global norm:
+    ///
+    /// - `{tag}/global_norm`: the L2 norm of all gradients together, i.e., the square root of the
+    ///   sum of the squares of `norms`;
+    /// - `{tag}/clipped`: `1.0` if the global norm exceeds `clip_threshold` (so clipping would
+    ///   trigger), or `0.0` otherwise.
+    pub fn gradient_norm(self, tag: &str, norms: &[f64], clip_threshold: f64) -> Self {
+        let global_norm = norms.iter().map(|n| n * n).sum::<f64>().sqrt();
+        let clipped = if global_norm > clip_threshold {
+            1.0
+        } else {
+            0.0
+        };
+        self.scalar(&format!("{}/global_norm", tag), global_norm as f32)
+            .scalar(&format!("{}/clipped", tag), clipped)
+    }
+
+    /// Adds summaries of a model's size, as scalars under `model/total_params`,
+    /// `model/trainable_params`, and (if given) `model/flops`. This is a handy thing to log once at
+    /// the start of a run.
+    ///
+    /// These are stored as 64-bit integer tensors, so that large counts are represented exactly,
+    /// unlike with [`scalar`][Self::scalar]. Counts above `i64::MAX` are clamped.
+    pub fn model_stats(self, total_params: u64, trainable_params: u64, flops: Option<u64>) -> Self {
+        let clamp = |n: u64| n.min(i64::MAX as u64) as i64;
+        let builder = self
+            .scalar_i64("model/total_params", clamp(total_params))
+            .scalar_i64("model/trainable_params", clamp(trainable_params));
+        match flops {
+            Some(flops) => builder.scalar_i64("model/flops", clamp(flops)),
+            None => builder,
+        }
+    }
+
     /// Adds a rank-0 text summary with a single string. The text is interpreted as Markdown.
     ///
     /// This can be used to log actual model outputs (e.g., predictions on some sample data at each
     /// step) or just as a general "escape hatch" to dump any human-readable data to TensorBoard.
-    pub fn text<T: AsRef<[u8]>>(self, tag: &str, text: &T) -> Self {
+    pub fn text<T: AsRef<[u8]> + ?Sized>(self, tag: &str, text: &T) -> Self {
         self.text_ndarray(tag, &[text], &[])
     }
 
@@ -67,7 +278,7 @@ impl SummaryBuilder {
     ///
     /// # Panics
     ///
-    /// In `cfg(debug)`, panics if the product of `shape` does not equal `text.len()`.
+    /// In debug builds, panics if the product of `shape` does not equal `text.len()`.
     pub fn text_ndarray<T: AsRef<[u8]>>(self, tag: &str, text: &[T], shape: &[usize]) -> Self {
         let string_val = text
             .iter()
@@ -83,90 +294,2498 @@ impl SummaryBuilder {
         shape: &[usize],
     ) -> Self {
         let mut tensor = pb::TensorProto::default();
+        debug_check_shape(shape, string_val.len());
+        tensor.tensor_shape = Some(tensor_shape(shape));
+        tensor.dtype = pb::DataType::DtString.into();
+        tensor.string_val = string_val;
 
-        // Check dimensions.
-        if cfg!(debug) {
-            let dim_product = shape
-                .iter()
-                .map(|&d| d as i64)
-                .try_fold(1i64, |x, y| x.checked_mul(y));
-            match dim_product {
-                Some(n) if n == string_val.len() as i64 => (),
-                None => panic!("bad shape: dimension product overflowed"),
-                Some(n) => {
-                    panic!(
-                        "bad shape: dimension product is {} but vector has length {}",
-                        n,
-                        string_val.len()
-                    );
-                }
+        const TEXT_PLUGIN_NAME: &str = "text";
+        let meta = plugin_metadata(TEXT_PLUGIN_NAME, Vec::new());
+        self.build_value(tag, InnerValue::Tensor(tensor), Some(meta))
+    }
+
+    /// Adds an image summary with one or more images of the same size, which TensorBoard shows
+    /// with a slider to page through them. Each image should already be encoded in a format that
+    /// browsers can display, like PNG or JPEG.
+    ///
+    /// The images plugin has no per-image captions, so any `captions` are listed in order in the
+    /// summary description, which TensorBoard shows alongside the images.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `captions` is given but has a different length than `images`.
+    pub fn image_list(
+        self,
+        tag: &str,
+        width: u32,
+        height: u32,
+        images: &[&[u8]],
+        captions: Option<&[&str]>,
+    ) -> Result<Self, LengthMismatch> {
+        let description = match captions {
+            None => String::new(),
+            Some(captions) if captions.len() != images.len() => {
+                return Err(LengthMismatch {
+                    what: "captions",
+                    got: captions.len(),
+                    want: images.len(),
+                });
             }
-        }
+            Some(captions) => captions
+                .iter()
+                .enumerate()
+                .map(|(i, caption)| format!("{}. {}\n", i + 1, caption.replace('\n', " ")))
+                .collect(),
+        };
+
+        // The images plugin expects a rank-1 string tensor: width and height as decimal strings,
+        // followed by the encoded images.
+        let mut string_val = Vec::with_capacity(images.len() + 2);
+        string_val.push(width.to_string().into());
+        string_val.push(height.to_string().into());
+        string_val.extend(
+            images
+                .iter()
+                .map(|image| prost::bytes::Bytes::copy_from_slice(image)),
+        );
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtString.into(),
+            tensor_shape: Some(tensor_shape(&[string_val.len()])),
+            string_val,
+            ..Default::default()
+        };
+
+        const IMAGES_PLUGIN_NAME: &str = "images";
+        let content = pb::ImagePluginData::default();
+        let meta = pb::SummaryMetadata {
+            data_class: pb::DataClass::BlobSequence.into(),
+            summary_description: description,
+            ..plugin_metadata(IMAGES_PLUGIN_NAME, prost::Message::encode_to_vec(&content))
+        };
+        Ok(self.build_value(tag, InnerValue::Tensor(tensor), Some(meta)))
+    }
+
+    /// Adds a text summary with a Markdown table of per-layer parameter counts, followed by a row
+    /// with the total count. This is a handy thing to log once at the start of a run.
+    pub fn param_summary(self, tag: &str, layers: &[(&str, usize)]) -> Self {
+        let total: usize = layers.iter().map(|&(_, count)| count).sum();
+        let mut rows: Vec<Vec<String>> = layers
+            .iter()
+            .map(|&(name, count)| vec![name.to_string(), count.to_string()])
+            .collect();
+        rows.push(vec!["**Total**".to_string(), total.to_string()]);
+        self.text(tag, &markdown_table(&["Layer", "Parameters"], &rows))
+    }
+
+    /// Adds a summary with a tensor of booleans, like an attention mask. The `data` vector should
+    /// be in row-major order with shape given by `shape`.
+    ///
+    /// No plugin owns boolean tensors, so this is for custom plugins and tools to read.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the product of `shape` does not equal `data.len()`.
+    pub fn tensor_bool(self, tag: &str, data: &[bool], shape: &[usize]) -> Self {
+        debug_check_shape(shape, data.len());
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtBool.into(),
+            tensor_shape: Some(tensor_shape(shape)),
+            bool_val: data.to_vec(),
+            ..Default::default()
+        };
+        self.build_value(tag, InnerValue::Tensor(tensor), None)
+    }
+
+    /// Adds a summary with a tensor of `f32`s for the plugin named `plugin_name`. The `values`
+    /// vector should be in row-major order with shape given by `shape`.
+    ///
+    /// This is a building block for plugins that this crate doesn't support directly. Values are
+    /// marked as tensors, with empty plugin content; for plugins that need content, use
+    /// [`tensor_with_plugin`][Self::tensor_with_plugin].
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the product of `shape` does not equal `values.len()`.
+    pub fn tensor_f32(self, tag: &str, values: &[f32], shape: &[usize], plugin_name: &str) -> Self {
+        debug_check_shape(shape, values.len());
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtFloat.into(),
+            float_val: values.to_vec(),
+            ..Default::default()
+        };
+        self.tensor_for_plugin(tag, tensor, shape, plugin_name)
+    }
+
+    /// Adds a summary with a tensor of `f64`s; see [`tensor_f32`][Self::tensor_f32].
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the product of `shape` does not equal `values.len()`.
+    pub fn tensor_f64(self, tag: &str, values: &[f64], shape: &[usize], plugin_name: &str) -> Self {
+        debug_check_shape(shape, values.len());
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtDouble.into(),
+            double_val: values.to_vec(),
+            ..Default::default()
+        };
+        self.tensor_for_plugin(tag, tensor, shape, plugin_name)
+    }
+
+    /// Adds a summary with a tensor of `i32`s; see [`tensor_f32`][Self::tensor_f32].
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the product of `shape` does not equal `values.len()`.
+    pub fn tensor_i32(self, tag: &str, values: &[i32], shape: &[usize], plugin_name: &str) -> Self {
+        debug_check_shape(shape, values.len());
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtInt32.into(),
+            int_val: values.to_vec(),
+            ..Default::default()
+        };
+        self.tensor_for_plugin(tag, tensor, shape, plugin_name)
+    }
+
+    /// Adds a summary with a tensor of `i64`s; see [`tensor_f32`][Self::tensor_f32].
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the product of `shape` does not equal `values.len()`.
+    pub fn tensor_i64(self, tag: &str, values: &[i64], shape: &[usize], plugin_name: &str) -> Self {
+        debug_check_shape(shape, values.len());
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtInt64.into(),
+            int64_val: values.to_vec(),
+            ..Default::default()
+        };
+        self.tensor_for_plugin(tag, tensor, shape, plugin_name)
+    }
+
+    /// Adds a summary with a tensor of byte strings (`DtString`), which may hold arbitrary binary
+    /// data in a format of your own; see [`tensor_f32`][Self::tensor_f32]. Unlike
+    /// [`text_ndarray`][Self::text_ndarray], this doesn't route the value to the text plugin.
+    ///
+    /// For example, to log a sparse tensor, one option is to follow TensorFlow's
+    /// `serialize_sparse` and write a rank-1 tensor of three strings: the serialized indices,
+    /// values, and dense shape.
+    ///
+    /// ```
+    /// use tensorboard_writer::SummaryBuilder;
+    ///
+    /// # let (indices, values, dense_shape): (Vec<u8>, Vec<u8>, Vec<u8>) = Default::default();
+    /// let parts: [&[u8]; 3] = [&indices, &values, &dense_shape];
+    /// let summ = SummaryBuilder::new()
+    ///     .tensor_bytes("grads/sparse", &parts, &[3], "my_sparse_plugin")
+    ///     .build();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the product of `shape` does not equal `values.len()`.
+    pub fn tensor_bytes<T: AsRef<[u8]>>(
+        self,
+        tag: &str,
+        values: &[T],
+        shape: &[usize],
+        plugin_name: &str,
+    ) -> Self {
+        debug_check_shape(shape, values.len());
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtString.into(),
+            string_val: values
+                .iter()
+                .map(|v| prost::bytes::Bytes::copy_from_slice(v.as_ref()))
+                .collect(),
+            ..Default::default()
+        };
+        self.tensor_for_plugin(tag, tensor, shape, plugin_name)
+    }
+
+    /// Adds a summary with the given tensor for the plugin named `plugin_name`, with
+    /// `plugin_content` (usually a serialized protobuf that the plugin defines) in its metadata.
+    ///
+    /// This is the most general way to write data for a plugin that this crate doesn't support
+    /// directly. The tensor is used as is, so it should already have its dtype and shape set.
+    /// The value is marked as a tensor, as with [`tensor_f32`][Self::tensor_f32].
+    pub fn tensor_with_plugin(
+        self,
+        tag: &str,
+        tensor: pb::TensorProto,
+        plugin_name: &str,
+        plugin_content: Vec<u8>,
+    ) -> Self {
+        let meta = pb::SummaryMetadata {
+            data_class: pb::DataClass::Tensor.into(),
+            ..plugin_metadata(plugin_name, plugin_content)
+        };
+        self.build_value(tag, InnerValue::Tensor(tensor), Some(meta))
+    }
+
+    fn tensor_for_plugin(
+        self,
+        tag: &str,
+        mut tensor: pb::TensorProto,
+        shape: &[usize],
+        plugin_name: &str,
+    ) -> Self {
+        tensor.tensor_shape = Some(tensor_shape(shape));
+        self.tensor_with_plugin(tag, tensor, plugin_name, Vec::new())
+    }
 
-        tensor.tensor_shape = Some({
-            let mut shape_pb = pb::TensorShapeProto::default();
-            shape_pb.dim = shape
+    /// Adds a confusion matrix as a rank-2 `int64` tensor, for a custom plugin to render. Row `i`
+    /// holds the counts of examples with true class `i`, and column `j` those predicted as class
+    /// `j`, so the tensor has shape `[n, n]` for `n` classes.
+    ///
+    /// The value is routed to a plugin named [`CONFUSION_MATRIX_PLUGIN_NAME`], which TensorBoard
+    /// itself doesn't have; use that name to find these summaries in your own tools.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the matrix is not square.
+    pub fn confusion_tensor(self, tag: &str, matrix: &[&[u32]]) -> Self {
+        let n = matrix.len();
+        debug_assert!(
+            matrix.iter().all(|row| row.len() == n),
+            "confusion matrix is not square: {} rows with lengths {:?}",
+            n,
+            matrix.iter().map(|row| row.len()).collect::<Vec<_>>()
+        );
+        let counts: Vec<i64> = matrix
+            .iter()
+            .flat_map(|row| row.iter().map(|&c| i64::from(c)))
+            .collect();
+        self.tensor_i64(tag, &counts, &[n, n], CONFUSION_MATRIX_PLUGIN_NAME)
+    }
+
+    /// Adds an audio summary with a pre-encoded WAV file, for TensorBoard's audio dashboard.
+    ///
+    /// This doesn't encode or inspect the audio itself, so `sample_rate`, `num_channels`, and
+    /// `length_frames` (samples per channel) should describe the contents of `encoded_wav`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `sample_rate` is not positive or `num_channels` is less than 1.
+    pub fn audio(
+        self,
+        tag: &str,
+        encoded_wav: &[u8],
+        sample_rate: f32,
+        num_channels: i64,
+        length_frames: i64,
+    ) -> Self {
+        debug_assert!(sample_rate > 0.0, "bad sample rate: {}", sample_rate);
+        debug_assert!(num_channels >= 1, "bad channel count: {}", num_channels);
+        let audio = pb::summary::Audio {
+            sample_rate,
+            num_channels,
+            length_frames,
+            encoded_audio_string: prost::bytes::Bytes::copy_from_slice(encoded_wav),
+            content_type: "audio/wav".to_string(),
+        };
+        const AUDIO_PLUGIN_NAME: &str = "audio";
+        let content = pb::AudioPluginData {
+            encoding: pb::audio_plugin_data::Encoding::Wav.into(),
+            ..Default::default()
+        };
+        let meta = plugin_metadata(AUDIO_PLUGIN_NAME, prost::Message::encode_to_vec(&content));
+        self.build_value(tag, InnerValue::Audio(audio), Some(meta))
+    }
+
+    /// Adds a precision–recall curve summary, for TensorBoard's PR curves dashboard.
+    ///
+    /// Each slice has one entry per threshold, in order of increasing threshold. The six series are
+    /// packed into a `[6, num_thresholds]` tensor, with rows in the order that the plugin expects:
+    /// true positives, false positives, true negatives, false negatives, precision, and recall.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if the slices don't all have the same length.
+    #[allow(clippy::too_many_arguments)]
+    pub fn pr_curve(
+        self,
+        tag: &str,
+        true_positives: &[f64],
+        false_positives: &[f64],
+        true_negatives: &[f64],
+        false_negatives: &[f64],
+        precision: &[f64],
+        recall: &[f64],
+    ) -> Self {
+        let rows = [
+            true_positives,
+            false_positives,
+            true_negatives,
+            false_negatives,
+            precision,
+            recall,
+        ];
+        let num_thresholds = true_positives.len();
+        debug_assert!(
+            rows.iter().all(|row| row.len() == num_thresholds),
+            "PR curve series have different lengths: {:?}",
+            rows.iter().map(|row| row.len()).collect::<Vec<_>>()
+        );
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtFloat.into(),
+            tensor_shape: Some(tensor_shape(&[rows.len(), num_thresholds])),
+            float_val: rows
                 .iter()
-                .map(|&d| pb::tensor_shape_proto::Dim {
-                    size: d as i64,
+                .flat_map(|row| row.iter().map(|&x| x as f32))
+                .collect(),
+            ..Default::default()
+        };
+        const PR_CURVES_PLUGIN_NAME: &str = "pr_curves";
+        let content = pb::PrCurvePluginData {
+            version: 0,
+            num_thresholds: num_thresholds as u32,
+        };
+        let meta = pb::SummaryMetadata {
+            data_class: pb::DataClass::Tensor.into(),
+            ..plugin_metadata(
+                PR_CURVES_PLUGIN_NAME,
+                prost::Message::encode_to_vec(&content),
+            )
+        };
+        self.build_value(tag, InnerValue::Tensor(tensor), Some(meta))
+    }
+
+    /// Adds a mesh or point cloud summary for the Mesh dashboard.
+    ///
+    /// `vertices` are 3D positions. Optionally, `faces` lists triangles as triples of indices into
+    /// `vertices`, and `colors` gives an RGB color per vertex. Without faces, the vertices are
+    /// shown as a point cloud.
+    ///
+    /// The mesh plugin expects each component in its own value, so this adds up to three values,
+    /// tagged `{tag}_VERTEX`, `{tag}_FACE`, and `{tag}_COLOR`. Each has a tensor with a leading
+    /// batch dimension of 1, and plugin data that names the mesh and lists which components it
+    /// has.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `colors` is given but doesn't have one color per vertex.
+    pub fn mesh(
+        self,
+        tag: &str,
+        vertices: &[[f32; 3]],
+        faces: Option<&[[i32; 3]]>,
+        colors: Option<&[[u8; 3]]>,
+    ) -> Self {
+        use pb::mesh_plugin_data::ContentType;
+        if let Some(colors) = colors {
+            debug_assert_eq!(
+                colors.len(),
+                vertices.len(),
+                "mesh needs one color per vertex"
+            );
+        }
+        // Each component is a `(content type, tag suffix, row count, tensor without shape)`.
+        let mut components = vec![(
+            ContentType::Vertex,
+            "VERTEX",
+            vertices.len(),
+            pb::TensorProto {
+                dtype: pb::DataType::DtFloat.into(),
+                float_val: vertices.iter().flatten().copied().collect(),
+                ..Default::default()
+            },
+        )];
+        if let Some(faces) = faces {
+            components.push((
+                ContentType::Face,
+                "FACE",
+                faces.len(),
+                pb::TensorProto {
+                    dtype: pb::DataType::DtInt32.into(),
+                    int_val: faces.iter().flatten().copied().collect(),
                     ..Default::default()
-                })
-                .collect();
-            shape_pb
+                },
+            ));
+        }
+        if let Some(colors) = colors {
+            components.push((
+                ContentType::Color,
+                "COLOR",
+                colors.len(),
+                pb::TensorProto {
+                    dtype: pb::DataType::DtUint8.into(),
+                    int_val: colors.iter().flatten().map(|&c| i32::from(c)).collect(),
+                    ..Default::default()
+                },
+            ));
+        }
+
+        const MESH_PLUGIN_NAME: &str = "mesh";
+        let mask = components.iter().fold(0u32, |mask, (content_type, ..)| {
+            mask | 1 << *content_type as u32
         });
+        let mut builder = self;
+        for (content_type, suffix, len, mut tensor) in components {
+            let shape = [1, len, 3];
+            tensor.tensor_shape = Some(tensor_shape(&shape));
+            let content = pb::MeshPluginData {
+                version: 0,
+                name: tag.to_string(),
+                content_type: content_type.into(),
+                json_config: "{}".to_string(),
+                shape: shape.iter().map(|&d| d as i32).collect(),
+                components: mask,
+            };
+            let meta = pb::SummaryMetadata {
+                display_name: tag.to_string(),
+                data_class: pb::DataClass::Tensor.into(),
+                ..plugin_metadata(MESH_PLUGIN_NAME, prost::Message::encode_to_vec(&content))
+            };
+            let component_tag = format!("{}_{}", tag, suffix);
+            builder = builder.build_value(&component_tag, InnerValue::Tensor(tensor), Some(meta));
+        }
+        builder
+    }
 
-        tensor.dtype = pb::DataType::DtString.into();
-        tensor.string_val = string_val;
+    /// Adds a text summary with a Markdown table that has both column and row headers.
+    ///
+    /// The table has a header row with `col_headers` (after an empty corner cell), then one row
+    /// per entry of `row_headers`, which is shown in bold in the leading column before that row's
+    /// `cells`. Pipes and newlines in headers and cells are escaped.
+    ///
+    /// # Errors
+    ///
+    /// Errors if there isn't exactly one row of `cells` per row header, or if any row doesn't have
+    /// exactly one cell per column header.
+    pub fn text_table(
+        self,
+        tag: &str,
+        col_headers: &[&str],
+        row_headers: &[&str],
+        cells: &[&[String]],
+    ) -> Result<Self, LengthMismatch> {
+        if cells.len() != row_headers.len() {
+            return Err(LengthMismatch {
+                what: "rows",
+                got: cells.len(),
+                want: row_headers.len(),
+            });
+        }
+        let mut header = vec![""];
+        header.extend_from_slice(col_headers);
+        let mut rows = Vec::with_capacity(cells.len());
+        for (row_header, row) in row_headers.iter().zip(cells) {
+            if row.len() != col_headers.len() {
+                return Err(LengthMismatch {
+                    what: "cells in a row",
+                    got: row.len(),
+                    want: col_headers.len(),
+                });
+            }
+            let mut cells = vec![format!("**{}**", row_header)];
+            cells.extend_from_slice(row);
+            rows.push(cells);
+        }
+        Ok(self.text(tag, &markdown_table(&header, &rows)))
+    }
 
-        let mut meta = pb::SummaryMetadata::default();
-        const TEXT_PLUGIN_NAME: &str = "text";
-        meta.plugin_data = Some(pb::summary_metadata::PluginData {
-            plugin_name: TEXT_PLUGIN_NAME.to_string(),
-            content: Default::default(),
-        });
+    /// Adds a text summary with a Markdown table comparing metrics across runs, e.g., the final
+    /// metrics of each run in a sweep.
+    ///
+    /// Each entry of `run_metrics` is a run name and that run's `(metric, value)` pairs. The table
+    /// has a row per run and a column per distinct metric, in order of first appearance. Cells for
+    /// metrics that a run doesn't have are left blank.
+    pub fn comparison_table(self, tag: &str, run_metrics: &[(&str, &[(&str, f64)])]) -> Self {
+        let mut metrics: Vec<&str> = Vec::new();
+        for (_, values) in run_metrics {
+            for (metric, _) in values.iter() {
+                if !metrics.contains(metric) {
+                    metrics.push(metric);
+                }
+            }
+        }
+        let rows: Vec<Vec<String>> = run_metrics
+            .iter()
+            .map(|(run, values)| {
+                let mut row = vec![run.to_string()];
+                row.extend(metrics.iter().map(|metric| {
+                    values
+                        .iter()
+                        .find(|(m, _)| m == metric)
+                        .map_or_else(String::new, |(_, value)| value.to_string())
+                }));
+                row
+            })
+            .collect();
+        let mut header = vec!["Run"];
+        header.extend(metrics);
+        self.text(tag, &markdown_table(&header, &rows))
+    }
 
-        self.build_value(tag, InnerValue::Tensor(tensor), Some(meta))
+    /// Adds a scalar summary with the precision of a binary classifier at the given target recall.
+    ///
+    /// Each of the `predictions` is a score for the corresponding entry of `labels`, with higher
+    /// scores meaning "more likely positive". The precision–recall curve is traced out by sweeping
+    /// a decision threshold down through the distinct scores. If no threshold gives exactly the
+    /// target `recall`, the precision is linearly interpolated between the two neighboring points
+    /// on the curve, where the curve starts at recall 0 with precision 1. If there are no positive
+    /// labels, the precision is NaN.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `predictions` and `labels` have different lengths.
+    pub fn precision_at_recall(
+        self,
+        tag: &str,
+        predictions: &[f32],
+        labels: &[bool],
+        recall: f64,
+    ) -> Self {
+        debug_assert_eq!(
+            predictions.len(),
+            labels.len(),
+            "predictions and labels must have the same length"
+        );
+        let precision = precision_at_recall(predictions, labels, recall);
+        self.scalar(tag, precision as f32)
+    }
+
+    /// Adds an image summary with a bar chart of the given counts, one bar per label, left to
+    /// right. The chart is rendered as a PNG whose width is proportional to the number of bars.
+    /// Since the image has no text, the labels and counts are listed in the summary description,
+    /// which TensorBoard shows alongside the image.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `labels` and `counts` have different lengths.
+    #[cfg(feature = "image")]
+    pub fn bar_chart(
+        self,
+        tag: &str,
+        labels: &[&str],
+        counts: &[u64],
+    ) -> Result<Self, LengthMismatch> {
+        if labels.len() != counts.len() {
+            return Err(LengthMismatch {
+                what: "counts",
+                got: counts.len(),
+                want: labels.len(),
+            });
+        }
+        let (width, height, pixels) = render_bar_chart(counts);
+        let image = pb::summary::Image {
+            width: width as i32,
+            height: height as i32,
+            colorspace: 3, // RGB
+            encoded_image_string: crate::png::encode_rgb(width, height, &pixels).into(),
+        };
+        let description: String = labels
+            .iter()
+            .zip(counts)
+            .map(|(label, count)| format!("- {}: {}\n", label, count))
+            .collect();
+        let meta = pb::SummaryMetadata {
+            summary_description: description,
+            ..Default::default()
+        };
+        Ok(self.build_value(tag, InnerValue::Image(image), Some(meta)))
     }
 
     /// Adds a histogram summary, linearly bucketing the given `values` into the given number of
     /// `bins`.
     ///
+    /// As in TensorFlow, each bucket includes its left edge but not its right edge, so there is
+    /// one extra bucket at the end with limit `+inf`, which holds the values equal to the maximum.
+    /// If all the values are equal, there's nothing to divide into bins, so the histogram has just
+    /// that one bucket.
+    ///
     /// The `values` may be `f32`s or `f64`s, or any type that can be copied into an `f64`.
     pub fn histogram<T>(self, tag: &str, bins: usize, values: &[T]) -> Self
     where
         T: Into<f64> + Copy,
     {
+        let histo = auto_range_histogram(bins, values);
+        self.build_value(tag, InnerValue::Histo(histo), None)
+    }
+
+    /// Adds a histogram summary like [`histogram`][Self::histogram], but with buckets spanning a
+    /// known range `[min, max]` instead of the range of the data. This saves a pass over the
+    /// values, which can matter for large tensors with known bounds (e.g., activations in
+    /// `[0, 1]`).
+    ///
+    /// Values below `min` are counted in the first bucket, and values at or above `max` in the
+    /// final overflow bucket. The histogram's `min` and `max` are set to the given range.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `min > max`.
+    pub fn histogram_known_range<T>(
+        self,
+        tag: &str,
+        bins: usize,
+        min: f64,
+        max: f64,
+        values: &[T],
+    ) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        debug_assert!(min <= max, "bad range: min {} > max {}", min, max);
         let mut histo = pb::HistogramProto::default();
-        if !values.is_empty() && bins > 0 {
-            histo.min = values
-                .iter()
-                .map(|z| Into::<f64>::into(*z))
-                .min_by(f64::total_cmp)
-                .unwrap();
-            histo.max = values
-                .iter()
-                .map(|z| Into::<f64>::into(*z))
-                .max_by(f64::total_cmp)
-                .unwrap();
-            // `bucket` has the counts in each bucket
-            histo.bucket = vec![0.0; bins];
-            // `bucket_limit` has the right edge of each bucket
-            histo.bucket_limit = Vec::with_capacity(bins);
-            let bucket_width = (histo.max - histo.min) / bins as f64;
-            for i in 0..bins {
-                histo
-                    .bucket_limit
-                    .push(histo.min + (i + 1) as f64 * bucket_width);
-            }
-            for z in values {
-                let idx = f64::floor((Into::<f64>::into(*z) - histo.min) / bucket_width);
-                // Clamp in case of any floating point weirdness.
-                let idx = idx.clamp(0.0, (bins - 1) as f64);
-                histo.bucket[idx as usize] += 1.0;
+        if bins > 0 {
+            histo = linear_histogram(bins, min, max, values.iter().map(|&z| z.into()));
+        }
+        self.build_value(tag, InnerValue::Histo(histo), None)
+    }
+
+    /// Adds a histogram summary with fixed bucket edges, so that histograms at different steps are
+    /// directly comparable.
+    ///
+    /// The `bucket_limits` are the right edges of the buckets, in increasing order. As in
+    /// [`histogram`][Self::histogram], each bucket includes its left edge but not its right edge:
+    /// the first bucket holds everything below `bucket_limits[0]`, and so on. Values at or above
+    /// the last limit go in an overflow bucket with limit `+inf`, which is added unless the last
+    /// limit is already `+inf`. The histogram's `min` and `max` are those of the data.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `bucket_limits` is not strictly increasing.
+    pub fn histogram_with_limits<T>(self, tag: &str, bucket_limits: &[f64], values: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let histo = fixed_limits_histogram(bucket_limits, values.iter().map(|&z| z.into()));
+        self.build_value(tag, InnerValue::Histo(histo), None)
+    }
+
+    /// Adds a histogram summary with TensorFlow's default buckets, like histograms written from
+    /// Python, without having to pick a bin count.
+    ///
+    /// The bucket limits grow exponentially by a factor of 1.1 away from zero, from `±1e-12` to
+    /// about `±1e20` (then `±f64::MAX`), so heavy-tailed distributions get fine resolution near
+    /// zero without running out of range. As in TensorFlow, runs of consecutive empty buckets are
+    /// collapsed into one, which keeps the summary small.
+    pub fn histogram_default_buckets<T>(self, tag: &str, values: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let limits = default_bucket_limits();
+        let mut counts = vec![0.0; limits.len()];
+        let mut moments = Moments::default();
+        let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+        for &z in values {
+            let z = z.into();
+            let i = limits.partition_point(|&limit| limit <= z);
+            counts[i.min(limits.len() - 1)] += 1.0;
+            moments.push(z);
+            min = min.min(z);
+            max = max.max(z);
+        }
+        let mut histo = pb::HistogramProto {
+            num: moments.count,
+            sum: moments.sum(),
+            sum_squares: moments.sum_squares(),
+            ..Default::default()
+        };
+        if moments.count > 0.0 {
+            histo.min = min;
+            histo.max = max;
+        }
+        for (i, (&limit, &count)) in limits.iter().zip(&counts).enumerate() {
+            // An empty bucket extends into the empty bucket after it, if any.
+            if count == 0.0 && counts.get(i + 1) == Some(&0.0) {
+                continue;
             }
-            // `histo` has other fields, like `sum` and `sum_squares`,
-            // but they don't actually matter :^)
+            histo.bucket_limit.push(limit);
+            histo.bucket.push(count);
+        }
+        self.build_value(tag, InnerValue::Histo(histo), None)
+    }
+
+    /// Adds a histogram summary like [`histogram`][Self::histogram], but with the bucket limits
+    /// and range rounded to the given number of decimal places.
+    ///
+    /// This makes the output stable against floating-point noise in the bucket edges, which helps
+    /// with golden-file tests and compression. The edges are rounded first and the values are then
+    /// counted into the rounded buckets, so the counts always match the edges shown. If rounding
+    /// makes adjacent edges equal, they're merged, so there may be fewer than `bins` buckets.
+    pub fn histogram_rounded<T>(self, tag: &str, bins: usize, values: &[T], decimals: u32) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let mut limits = auto_range_histogram(bins, values).bucket_limit;
+        if limits.is_empty() {
+            return self.build_value(tag, InnerValue::Histo(Default::default()), None);
+        }
+        for limit in &mut limits {
+            *limit = round_to(*limit, decimals);
         }
+        // Rounding never reorders the edges, but it can make neighbors equal.
+        limits.dedup();
+        let mut histo = fixed_limits_histogram(&limits, values.iter().map(|&z| z.into()));
+        histo.min = round_to(histo.min, decimals);
+        histo.max = round_to(histo.max, decimals);
         self.build_value(tag, InnerValue::Histo(histo), None)
     }
+
+    /// Adds a histogram summary as with [`histogram`][Self::histogram], plus scalar summaries of
+    /// the same values under `{tag}/mean`, `{tag}/std`, `{tag}/min`, and `{tag}/max`.
+    ///
+    /// The standard deviation is the population standard deviation. If `values` is empty, only the
+    /// (empty) histogram is added.
+    pub fn histogram_with_stats<T>(self, tag: &str, bins: usize, values: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let builder = self.histogram(tag, bins, values);
+        if values.is_empty() {
+            return builder;
+        }
+        let mut moments = Moments::default();
+        let (mut min, mut max) = (f64::INFINITY, f64::NEG_INFINITY);
+        for &z in values {
+            let z = z.into();
+            moments.push(z);
+            min = min.min(z);
+            max = max.max(z);
+        }
+        let std = (moments.m2 / moments.count).sqrt();
+        builder
+            .scalar(&format!("{}/mean", tag), moments.mean as f32)
+            .scalar(&format!("{}/std", tag), std as f32)
+            .scalar(&format!("{}/min", tag), min as f32)
+            .scalar(&format!("{}/max", tag), max as f32)
+    }
+
+    /// Adds several histogram summaries at once, each with the given number of `bins` and its own
+    /// range, as with [`histogram`][Self::histogram].
+    pub fn histograms(self, bins: usize, entries: &[(&str, &[f64])]) -> Self {
+        entries.iter().fold(self, |builder, (tag, values)| {
+            builder.histogram(tag, bins, values)
+        })
+    }
+
+    /// Adds a histogram summary from precomputed counts in equal-width bins spanning `[min, max]`.
+    ///
+    /// There is one bucket per entry of `counts`, and the bucket limits are derived from the
+    /// range. Unlike [`histogram`][Self::histogram], there is no overflow bucket, since anything
+    /// out of range has presumably already been counted into the first or last bin.
+    pub fn histogram_uniform_counts(self, tag: &str, min: f64, max: f64, counts: &[u64]) -> Self {
+        debug_assert!(min <= max, "bad range: min {} > max {}", min, max);
+        let bins = counts.len();
+        let bucket_width = (max - min) / bins as f64;
+        let mut bucket_limit: Vec<f64> = (1..bins).map(|i| min + i as f64 * bucket_width).collect();
+        if bins > 0 {
+            // Use `max` itself rather than accumulating rounding error into the last edge.
+            bucket_limit.push(max);
+        }
+        let histo = pb::HistogramProto {
+            min,
+            max,
+            num: counts.iter().sum::<u64>() as f64,
+            bucket_limit,
+            bucket: counts.iter().map(|&c| c as f64).collect(),
+            ..Default::default()
+        };
+        self.build_value(tag, InnerValue::Histo(histo), None)
+    }
+
+    /// Adds a histogram summary with all of its fields given directly, like TensorFlow's
+    /// `histogram_raw`, for counts that were aggregated elsewhere.
+    ///
+    /// As in the other histograms, `bucket_limits` are the right edges of the buckets, in
+    /// increasing order, and `bucket_counts` are the number of values in each bucket. The
+    /// statistics are taken as given, and aren't checked against the counts.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `bucket_limits` and `bucket_counts` have different lengths.
+    #[allow(clippy::too_many_arguments)]
+    pub fn histogram_raw(
+        self,
+        tag: &str,
+        min: f64,
+        max: f64,
+        num: f64,
+        sum: f64,
+        sum_squares: f64,
+        bucket_limits: &[f64],
+        bucket_counts: &[f64],
+    ) -> Self {
+        debug_assert_eq!(
+            bucket_limits.len(),
+            bucket_counts.len(),
+            "bucket limits and counts must have the same length"
+        );
+        let histo = pb::HistogramProto {
+            min,
+            max,
+            num,
+            sum,
+            sum_squares,
+            bucket_limit: bucket_limits.to_vec(),
+            bucket: bucket_counts.to_vec(),
+        };
+        self.build_value(tag, InnerValue::Histo(histo), None)
+    }
+
+    /// Adds a histogram summary of a sparse vector, linearly bucketing its values into the given
+    /// number of `bins`.
+    ///
+    /// The vector has length `total_len`, and is zero except at the given `indices`, where it takes
+    /// the corresponding `values`. The implied zeros are counted into the bucket containing `0.0`
+    /// without being materialized, and the range of the histogram includes `0.0` if there are any.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `indices` and `values` have different lengths, or if any index is out of bounds
+    /// for `total_len` or appears more than once.
+    pub fn histogram_sparse(
+        self,
+        tag: &str,
+        indices: &[usize],
+        values: &[f64],
+        total_len: usize,
+        bins: usize,
+    ) -> Self {
+        assert_eq!(
+            indices.len(),
+            values.len(),
+            "indices and values must have the same length"
+        );
+        let mut sorted = indices.to_vec();
+        sorted.sort_unstable();
+        if let Some(&last) = sorted.last() {
+            assert!(
+                last < total_len,
+                "index {} out of bounds for length {}",
+                last,
+                total_len
+            );
+        }
+        if let Some(pair) = sorted.windows(2).find(|pair| pair[0] == pair[1]) {
+            panic!("index {} appears more than once", pair[0]);
+        }
+        // The indices are distinct and in bounds, so this doesn't underflow.
+        let zeros = total_len - values.len();
+        let mut histo = pb::HistogramProto::default();
+        if total_len > 0 && bins > 0 {
+            let implied = if zeros > 0 { Some(0.0) } else { None };
+            let range = values.iter().copied().chain(implied);
+            let min = range.clone().min_by(f64::total_cmp).unwrap();
+            let max = range.max_by(f64::total_cmp).unwrap();
+            histo = linear_histogram(bins, min, max, values.iter().copied());
+            if zeros > 0 {
+                // Zeros don't change `sum` or `sum_squares`, just the count.
+                histo.bucket[linear_bucket_index(min, max, bins, 0.0)] += zeros as f64;
+                histo.num += zeros as f64;
+            }
+        }
+        self.build_value(tag, InnerValue::Histo(histo), None)
+    }
+
+    /// Adds a histogram summary like [`histogram`][Self::histogram], but picks the number of bins
+    /// automatically from the data.
+    ///
+    /// The bin width is chosen by the Freedman–Diaconis rule (twice the interquartile range over
+    /// the cube root of the number of values). If the interquartile range is zero, this falls back
+    /// to Sturges' rule, which only looks at the number of values. Constant data and inputs with
+    /// fewer than two values get a single bin.
+    pub fn histogram_auto<T>(self, tag: &str, values: &[T]) -> Self
+    where
+        T: Into<f64> + Copy,
+    {
+        let floats: Vec<f64> = values.iter().map(|&z| z.into()).collect();
+        self.histogram(tag, auto_bin_count(&floats), &floats)
+    }
+}
+
+/// Appends `data` to `buf` as a length-delimited protobuf field with the given number.
+pub(crate) fn encode_length_delimited(field: u32, data: &[u8], buf: &mut Vec<u8>) {
+    use prost::encoding::{encode_key, encode_varint, WireType};
+    encode_key(field, WireType::LengthDelimited, buf);
+    encode_varint(data.len() as u64, buf);
+    buf.extend_from_slice(data);
+}
+
+/// In debug builds, panics if the product of `shape` does not equal `len`.
+fn debug_check_shape(shape: &[usize], len: usize) {
+    if cfg!(debug_assertions) {
+        let dim_product = shape
+            .iter()
+            .map(|&d| d as i64)
+            .try_fold(1i64, |x, y| x.checked_mul(y));
+        match dim_product {
+            Some(n) if n == len as i64 => (),
+            None => panic!("bad shape: dimension product overflowed"),
+            Some(n) => {
+                panic!(
+                    "bad shape: dimension product is {} but vector has length {}",
+                    n, len
+                );
+            }
+        }
+    }
+}
+
+/// Builds a tensor shape proto with the given dimensions.
+fn tensor_shape(shape: &[usize]) -> pb::TensorShapeProto {
+    pb::TensorShapeProto {
+        dim: shape
+            .iter()
+            .map(|&d| pb::tensor_shape_proto::Dim {
+                size: d as i64,
+                ..Default::default()
+            })
+            .collect(),
+        ..Default::default()
+    }
+}
+
+/// Builds summary metadata that routes a value to the given plugin, with the given plugin-specific
+/// content (usually a serialized protobuf, or empty).
+fn plugin_metadata(plugin_name: &str, content: Vec<u8>) -> pb::SummaryMetadata {
+    pb::SummaryMetadata {
+        plugin_data: Some(pb::summary_metadata::PluginData {
+            plugin_name: plugin_name.to_string(),
+            content: content.into(),
+        }),
+        ..Default::default()
+    }
+}
+
+/// Builds summary metadata for a tensor-valued scalar, which routes it to the scalars dashboard.
+fn scalar_metadata() -> pb::SummaryMetadata {
+    pb::SummaryMetadata {
+        data_class: pb::DataClass::Scalar.into(),
+        ..plugin_metadata("scalars", Vec::new())
+    }
+}
+
+/// Computes an interpolated precision at a target recall; see
+/// [`SummaryBuilder::precision_at_recall`].
+fn precision_at_recall(predictions: &[f32], labels: &[bool], target: f64) -> f64 {
+    let mut scored: Vec<(f32, bool)> = predictions
+        .iter()
+        .copied()
+        .zip(labels.iter().copied())
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let positives = scored.iter().filter(|&&(_, label)| label).count();
+    if positives == 0 {
+        return f64::NAN;
+    }
+
+    let (mut prev_recall, mut prev_precision) = (0.0, 1.0);
+    let (mut tp, mut fp) = (0usize, 0usize);
+    let mut i = 0;
+    while i < scored.len() {
+        // Take all examples tied at this score at once, since no threshold can separate them.
+        let score = scored[i].0;
+        while i < scored.len() && scored[i].0 == score {
+            if scored[i].1 {
+                tp += 1;
+            } else {
+                fp += 1;
+            }
+            i += 1;
+        }
+        let recall = tp as f64 / positives as f64;
+        let precision = tp as f64 / (tp + fp) as f64;
+        if recall >= target {
+            if recall == prev_recall {
+                return precision;
+            }
+            let t = (target - prev_recall) / (recall - prev_recall);
+            return prev_precision + t * (precision - prev_precision);
+        }
+        prev_recall = recall;
+        prev_precision = precision;
+    }
+    // Only reachable if `target > 1`: extrapolate no further than full recall.
+    prev_precision
+}
+
+/// Width in pixels of each bar in [`SummaryBuilder::bar_chart`], including the gap to its right.
+#[cfg(feature = "image")]
+const BAR_PITCH: u32 = 20;
+/// Width in pixels of the gap between bars in [`SummaryBuilder::bar_chart`].
+#[cfg(feature = "image")]
+const BAR_GAP: u32 = 4;
+/// Height in pixels of [`SummaryBuilder::bar_chart`] images.
+#[cfg(feature = "image")]
+const BAR_CHART_HEIGHT: u32 = 128;
+
+/// Renders a bar chart as RGB pixels, scaled so that the largest count fills the height. Returns
+/// the width, height, and pixel data.
+#[cfg(feature = "image")]
+fn render_bar_chart(counts: &[u64]) -> (u32, u32, Vec<u8>) {
+    const BACKGROUND: [u8; 3] = [255, 255, 255];
+    const BAR: [u8; 3] = [255, 112, 67];
+
+    let width = BAR_PITCH * (counts.len() as u32).max(1);
+    let height = BAR_CHART_HEIGHT;
+    let max_count = counts.iter().copied().max().unwrap_or(0);
+    let bar_heights: Vec<u32> = counts
+        .iter()
+        .map(|&c| match max_count {
+            0 => 0,
+            m => ((c as f64 / m as f64) * height as f64).round() as u32,
+        })
+        .collect();
+
+    let mut pixels = Vec::with_capacity((width * height * 3) as usize);
+    for y in 0..height {
+        let from_bottom = height - y;
+        for x in 0..width {
+            let bar = (x / BAR_PITCH) as usize;
+            let in_bar = x % BAR_PITCH < BAR_PITCH - BAR_GAP
+                && bar_heights.get(bar).is_some_and(|&h| from_bottom <= h);
+            pixels.extend(if in_bar { BAR } else { BACKGROUND });
+        }
+    }
+    (width, height, pixels)
+}
+
+/// Builds a histogram with `bins` equal-width buckets spanning the range of `values`; see
+/// [`SummaryBuilder::histogram`]. The histogram is empty if there are no values or no bins.
+fn auto_range_histogram<T>(bins: usize, values: &[T]) -> pb::HistogramProto
+where
+    T: Into<f64> + Copy,
+{
+    if values.is_empty() || bins == 0 {
+        return pb::HistogramProto::default();
+    }
+    let min = values
+        .iter()
+        .map(|z| Into::<f64>::into(*z))
+        .min_by(f64::total_cmp)
+        .unwrap();
+    let max = values
+        .iter()
+        .map(|z| Into::<f64>::into(*z))
+        .max_by(f64::total_cmp)
+        .unwrap();
+    linear_histogram(bins, min, max, values.iter().map(|&z| z.into()))
+}
+
+/// Builds a histogram with `bins` equal-width buckets spanning `[min, max)` plus an overflow
+/// bucket with limit `+inf`, and counts `values` into them. Values below `min` are clamped into
+/// the first bucket, and values at or above `max` go in the overflow bucket.
+///
+/// If `min == max`, the finite buckets would all be empty and zero-width, so there's only the
+/// overflow bucket, which holds every value.
+fn linear_histogram<I>(bins: usize, min: f64, max: f64, values: I) -> pb::HistogramProto
+where
+    I: IntoIterator<Item = f64>,
+{
+    if min == max {
+        let mut moments = Moments::default();
+        values.into_iter().for_each(|z| moments.push(z));
+        return pb::HistogramProto {
+            min,
+            max,
+            num: moments.count,
+            sum: moments.sum(),
+            sum_squares: moments.sum_squares(),
+            bucket_limit: vec![f64::INFINITY],
+            bucket: vec![moments.count],
+        };
+    }
+    let mut histo = pb::HistogramProto {
+        min,
+        max,
+        // `bucket` has the counts in each bucket
+        bucket: vec![0.0; bins + 1],
+        // `bucket_limit` has the (exclusive) right edge of each bucket
+        bucket_limit: Vec::with_capacity(bins + 1),
+        ..Default::default()
+    };
+    let bucket_width = (max - min) / bins as f64;
+    for i in 1..bins {
+        histo.bucket_limit.push(min + i as f64 * bucket_width);
+    }
+    // Computing the last edge as `min + bins * bucket_width` can land just below `max`.
+    histo.bucket_limit.push(max);
+    histo.bucket_limit.push(f64::INFINITY);
+    let mut moments = Moments::default();
+    for z in values {
+        histo.bucket[linear_bucket_index(min, max, bins, z)] += 1.0;
+        moments.push(z);
+    }
+    histo.num = moments.count;
+    histo.sum = moments.sum();
+    histo.sum_squares = moments.sum_squares();
+    histo
+}
+
+/// The right edges of TensorFlow's default histogram buckets; see
+/// [`SummaryBuilder::histogram_default_buckets`].
+fn default_bucket_limits() -> Vec<f64> {
+    let mut positive = Vec::new();
+    let mut v = 1e-12;
+    while v < 1e20 {
+        positive.push(v);
+        v *= 1.1;
+    }
+    positive.push(f64::MAX);
+    let mut limits: Vec<f64> = positive.iter().rev().map(|&v| -v).collect();
+    limits.push(0.0);
+    limits.extend(positive);
+    limits
+}
+
+/// Builds a histogram with the given bucket right edges, plus an overflow bucket with limit `+inf`
+/// if the last edge isn't already infinite, and counts `values` into them.
+fn fixed_limits_histogram<I>(limits: &[f64], values: I) -> pb::HistogramProto
+where
+    I: IntoIterator<Item = f64>,
+{
+    let mut acc = HistogramAccumulator::new(limits);
+    for z in values {
+        acc.add(z);
+    }
+    acc.finish()
+}
+
+/// Counts values into a histogram with fixed buckets one at a time, for data that is too large to
+/// collect into a slice for [`SummaryBuilder::histogram_with_limits`].
+///
+/// The buckets are as for `histogram_with_limits`, and the result is the same as if all the
+/// values had been passed to it at once. Only the bucket counts and a few running statistics are
+/// stored, so memory use doesn't grow with the number of values.
+///
+/// ```
+/// use tensorboard_writer::proto::tensorboard as pb;
+/// use tensorboard_writer::{HistogramAccumulator, SummaryBuilder};
+///
+/// let mut acc = HistogramAccumulator::new(&[-1.0, 0.0, 1.0]);
+/// for chunk in [[0.5, -0.25], [2.0, 0.0]].iter() {
+///     acc.add_slice(chunk);
+/// }
+/// let histo = acc.finish();
+/// assert_eq!(histo.num, 4.0);
+/// let value = pb::summary::Value {
+///     tag: "weights".to_string(),
+///     value: Some(pb::summary::value::Value::Histo(histo)),
+///     ..Default::default()
+/// };
+/// let summary = SummaryBuilder::new().value(value).build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct HistogramAccumulator {
+    histo: pb::HistogramProto,
+    moments: Moments,
+    min: f64,
+    max: f64,
+}
+
+impl HistogramAccumulator {
+    /// Creates an accumulator with the given bucket right edges, in increasing order. An overflow
+    /// bucket with limit `+inf` is added unless the last limit is already `+inf`.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, panics if `bucket_limits` is not strictly increasing.
+    pub fn new(bucket_limits: &[f64]) -> Self {
+        debug_assert!(
+            bucket_limits.windows(2).all(|w| w[0] < w[1]),
+            "bucket limits are not strictly increasing: {:?}",
+            bucket_limits
+        );
+        let mut bucket_limit = bucket_limits.to_vec();
+        if bucket_limit.last() != Some(&f64::INFINITY) {
+            bucket_limit.push(f64::INFINITY);
+        }
+        HistogramAccumulator {
+            histo: pb::HistogramProto {
+                bucket: vec![0.0; bucket_limit.len()],
+                bucket_limit,
+                ..Default::default()
+            },
+            moments: Moments::default(),
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    /// Counts one value.
+    pub fn add(&mut self, value: f64) {
+        let i = self
+            .histo
+            .bucket_limit
+            .partition_point(|&limit| limit <= value);
+        // Only `+inf` itself is past the last limit; it belongs in the overflow bucket too.
+        let last = self.histo.bucket.len() - 1;
+        self.histo.bucket[i.min(last)] += 1.0;
+        self.moments.push(value);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Counts each of the given values.
+    pub fn add_slice(&mut self, values: &[f64]) {
+        for &z in values {
+            self.add(z);
+        }
+    }
+
+    /// Returns the histogram of all the values added so far. Its `min` and `max` are those of the
+    /// data, or zero if there were no values.
+    pub fn finish(self) -> pb::HistogramProto {
+        let mut histo = self.histo;
+        if self.moments.count > 0.0 {
+            histo.min = self.min;
+            histo.max = self.max;
+        }
+        histo.num = self.moments.count;
+        histo.sum = self.moments.sum();
+        histo.sum_squares = self.moments.sum_squares();
+        histo
+    }
+}
+
+/// Running mean and variance of a stream of values, by Welford's algorithm.
+///
+/// Accumulating `sum` and `sum_squares` directly loses precision when the values have a large
+/// offset relative to their spread (e.g., values around `1e6` that differ by `0.01`), because the
+/// squares are huge and nearly equal. Tracking the mean and the sum of squared deviations from it
+/// avoids that, and the raw sums can be reconstructed at the end.
+#[derive(Debug, Clone, Default)]
+struct Moments {
+    count: f64,
+    mean: f64,
+    /// Sum of squared deviations from the mean.
+    m2: f64,
+}
+
+impl Moments {
+    fn push(&mut self, x: f64) {
+        self.count += 1.0;
+        let delta = x - self.mean;
+        self.mean += delta / self.count;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    fn sum(&self) -> f64 {
+        self.count * self.mean
+    }
+
+    fn sum_squares(&self) -> f64 {
+        self.m2 + self.count * self.mean * self.mean
+    }
+}
+
+/// Finds the index of the bucket containing `z` among `bins` equal-width buckets spanning
+/// `[min, max)`, where index `bins` is the overflow bucket; see [`linear_histogram`]. If
+/// `min == max`, the overflow bucket is the only one, at index 0.
+fn linear_bucket_index(min: f64, max: f64, bins: usize, z: f64) -> usize {
+    if min == max {
+        return 0;
+    }
+    if z >= max {
+        return bins;
+    }
+    let bucket_width = (max - min) / bins as f64;
+    let idx = f64::floor((z - min) / bucket_width);
+    // Clamp in case of any floating point weirdness.
+    idx.clamp(0.0, (bins - 1) as f64) as usize
+}
+
+/// Rounds `x` to the given number of decimal places. Values that are too large to round at that
+/// precision (including infinities) are returned unchanged.
+fn round_to(x: f64, decimals: u32) -> f64 {
+    let scale = 10f64.powi(decimals.min(i32::MAX as u32) as i32);
+    let rounded = (x * scale).round() / scale;
+    if rounded.is_finite() {
+        rounded
+    } else {
+        x
+    }
+}
+
+/// Maximum number of bins that [`SummaryBuilder::histogram_auto`] will choose.
+const MAX_AUTO_BINS: usize = 512;
+
+/// Chooses a histogram bin count for `values`; see [`SummaryBuilder::histogram_auto`].
+fn auto_bin_count(values: &[f64]) -> usize {
+    let n = values.len();
+    if n <= 1 {
+        return 1;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let range = sorted[n - 1] - sorted[0];
+    if !(range > 0.0 && range.is_finite()) {
+        return 1;
+    }
+    let iqr = quantile(&sorted, 0.75) - quantile(&sorted, 0.25);
+    let bins = if iqr > 0.0 {
+        let width = 2.0 * iqr / (n as f64).cbrt();
+        (range / width).ceil() as usize
+    } else {
+        (n as f64).log2().ceil() as usize + 1
+    };
+    bins.clamp(1, MAX_AUTO_BINS)
+}
+
+/// Computes a quantile of sorted data by linear interpolation between closest ranks.
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (pos - lo as f64)
+}
+
+/// Formats a Markdown table with the given header row and body rows. Pipes and newlines in cells
+/// are escaped so that they can't break the table structure.
+pub(crate) fn markdown_table<S: AsRef<str>>(header: &[S], rows: &[Vec<String>]) -> String {
+    fn push_row<S: AsRef<str>>(out: &mut String, cells: &[S]) {
+        out.push('|');
+        for cell in cells {
+            out.push(' ');
+            out.push_str(&cell.as_ref().replace('|', "\\|").replace('\n', " "));
+            out.push_str(" |");
+        }
+        out.push('\n');
+    }
+    let mut out = String::new();
+    push_row(&mut out, header);
+    push_row(&mut out, &vec!["---"; header.len()]);
+    for row in rows {
+        push_row(&mut out, row);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Extracts the strings from a text summary value, panicking if it's not a text summary.
+    fn text_of(value: &pb::summary::Value) -> Vec<String> {
+        match &value.value {
+            Some(InnerValue::Tensor(t)) => t
+                .string_val
+                .iter()
+                .map(|b| String::from_utf8(b.to_vec()).unwrap())
+                .collect(),
+            other => panic!("not a text summary: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_markdown_table() {
+        let rows = vec![
+            vec!["a|b".to_string(), "1".to_string()],
+            vec!["c\nd".to_string(), "2".to_string()],
+        ];
+        assert_eq!(
+            markdown_table(&["name", "value"], &rows),
+            "| name | value |\n| --- | --- |\n| a\\|b | 1 |\n| c d | 2 |\n"
+        );
+    }
+
+    #[test]
+    fn test_param_summary() {
+        let layers = [("conv1", 896), ("conv2", 18496), ("dense", 1290)];
+        let summ = SummaryBuilder::new()
+            .param_summary("params", &layers)
+            .build();
+        assert_eq!(summ.value.len(), 1);
+        assert_eq!(summ.value[0].tag, "params");
+        let text = text_of(&summ.value[0]).concat();
+        let lines: Vec<&str> = text.lines().collect();
+        // header, separator, one row per layer, total
+        assert_eq!(lines.len(), 2 + layers.len() + 1);
+        assert_eq!(lines[2], "| conv1 | 896 |");
+        let total: usize = layers.iter().map(|&(_, n)| n).sum();
+        assert_eq!(lines.last().unwrap(), &format!("| **Total** | {} |", total));
+    }
+
+    fn histo_of(value: &pb::summary::Value) -> &pb::HistogramProto {
+        match &value.value {
+            Some(InnerValue::Histo(h)) => h,
+            other => panic!("not a histogram: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_histogram_auto() {
+        // n = 100, IQR = 49.5, so the Freedman–Diaconis width is 99 / cbrt(100) ~= 21.3, which
+        // covers the range of 99 in 5 bins.
+        let values: Vec<f64> = (0..100).map(f64::from).collect();
+        let summ = SummaryBuilder::new().histogram_auto("h", &values).build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!(histo.bucket.len(), 5 + 1); // plus overflow bucket
+        assert_eq!(histo.bucket.iter().sum::<f64>(), 100.0);
+    }
+
+    #[test]
+    fn test_histogram_auto_degenerate() {
+        let summ = SummaryBuilder::new()
+            .histogram_auto("constant", &[3.0f32; 10])
+            .histogram_auto("single", &[1.0])
+            .histogram_auto("empty", &[] as &[f64])
+            .build();
+        // All values are equal, so there's just the overflow bucket.
+        assert_eq!(histo_of(&summ.value[0]).bucket, vec![10.0]);
+        assert_eq!(histo_of(&summ.value[1]).bucket, vec![1.0]);
+        assert_eq!(histo_of(&summ.value[2]).bucket, Vec::<f64>::new());
+    }
+
+    #[test]
+    fn test_histogram_constant() {
+        let summ = SummaryBuilder::new().histogram("h", 30, &[2.5; 4]).build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!((histo.min, histo.max, histo.num), (2.5, 2.5, 4.0));
+        assert_eq!(histo.bucket_limit, vec![f64::INFINITY]);
+        assert_eq!(histo.bucket, vec![4.0]);
+    }
+
+    #[test]
+    fn test_auto_bin_count_zero_iqr() {
+        // More than half the mass at one point, so the IQR is zero: use Sturges' rule.
+        let mut values = vec![0.0; 30];
+        values.push(1.0);
+        values.push(2.0);
+        assert_eq!(auto_bin_count(&values), 6); // ceil(log2(32)) + 1
+    }
+
+    /// A `Summary.Value` from a newer version of the proto, with a kind of value (field 42 of the
+    /// `value` oneof) that this crate doesn't know about.
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct FutureValue {
+        #[prost(string, tag = "1")]
+        tag: String,
+        #[prost(bytes = "vec", tag = "42")]
+        future: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct FutureSummary {
+        #[prost(message, repeated, tag = "1")]
+        value: Vec<FutureValue>,
+    }
+
+    #[test]
+    fn test_value_raw() {
+        use prost::Message;
+        let serialized = FutureValue {
+            tag: String::new(),
+            future: b"new kind".to_vec(),
+        }
+        .encode_to_vec();
+        let builder = || {
+            SummaryBuilder::new()
+                .value_raw("future", &serialized)
+                .scalar("loss", 1.0)
+        };
+
+        // The raw value round-trips, after the other values.
+        let encoded = builder().build_encoded();
+        let values = FutureSummary::decode(&encoded[..]).unwrap().value;
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[0].tag, "loss");
+        let expected = FutureValue {
+            tag: "future".to_string(),
+            future: b"new kind".to_vec(),
+        };
+        assert_eq!(values[1], expected);
+
+        // Decoding into this crate's protos keeps just the tag.
+        let summ = builder().build();
+        assert_eq!(summ, pb::Summary::decode(&encoded[..]).unwrap());
+        assert_eq!(summ.value[1].tag, "future");
+        assert_eq!(summ.value[1].value, None);
+
+        // Removing the tag removes the raw value, too.
+        let summ = builder().remove("future").build_encoded();
+        assert_eq!(FutureSummary::decode(&summ[..]).unwrap().value.len(), 1);
+    }
+
+    #[test]
+    fn test_precision_at_recall() {
+        // Sweeping the threshold gives (recall, precision) points:
+        // (1/3, 1), (1/3, 1/2), (2/3, 2/3), (1, 3/4), (1, 3/5).
+        let predictions = [0.9, 0.8, 0.7, 0.6, 0.5];
+        let labels = [true, false, true, true, false];
+        let at = |recall| precision_at_recall(&predictions, &labels, recall);
+        assert!((at(2.0 / 3.0) - 2.0 / 3.0).abs() < 1e-12); // exact point
+        assert!((at(0.5) - 7.0 / 12.0).abs() < 1e-12); // halfway from 1/2 to 2/3
+        assert!((at(0.9) - 0.725).abs() < 1e-12); // 70% of the way from 2/3 to 3/4
+        assert!((at(0.1) - 1.0).abs() < 1e-12); // between the origin and the first point
+        assert!((at(1.0) - 0.75).abs() < 1e-12); // first threshold reaching full recall
+        assert!(precision_at_recall(&[0.5], &[false], 0.5).is_nan());
+
+        let summ = SummaryBuilder::new()
+            .precision_at_recall("p@r0.5", &predictions, &labels, 0.5)
+            .build();
+        assert_eq!(
+            summ.value[0].value,
+            Some(InnerValue::SimpleValue((7.0f64 / 12.0) as f32))
+        );
+    }
+
+    #[test]
+    fn test_precision_at_recall_ties() {
+        // The two examples tied at 0.8 are taken together: (1/2, 1/2), then (1, 2/3).
+        let predictions = [0.8, 0.8, 0.3];
+        let labels = [true, false, true];
+        let p = precision_at_recall(&predictions, &labels, 0.5);
+        assert!((p - 0.5).abs() < 1e-12);
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_bar_chart() {
+        let summ = SummaryBuilder::new()
+            .bar_chart("classes", &["cat", "dog", "bird"], &[10, 5, 0])
+            .unwrap()
+            .build();
+        let image = match &summ.value[0].value {
+            Some(InnerValue::Image(image)) => image,
+            other => panic!("not an image: {:?}", other),
+        };
+        assert_eq!(image.width as u32, 3 * BAR_PITCH);
+        assert_eq!(image.height as u32, BAR_CHART_HEIGHT);
+        assert!(image.encoded_image_string.starts_with(b"\x89PNG"));
+        let meta = summ.value[0].metadata.as_ref().unwrap();
+        assert_eq!(meta.summary_description, "- cat: 10\n- dog: 5\n- bird: 0\n");
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_bar_chart_length_mismatch() {
+        let err = SummaryBuilder::new()
+            .bar_chart("classes", &["cat", "dog"], &[1, 2, 3])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            LengthMismatch {
+                what: "counts",
+                got: 3,
+                want: 2
+            }
+        );
+        assert_eq!(err.to_string(), "length mismatch: got 3 counts, want 2");
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_render_bar_chart() {
+        let (width, height, pixels) = render_bar_chart(&[2, 1]);
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * width + x) * 3) as usize;
+            pixels[i..i + 3].to_vec()
+        };
+        let white = vec![255, 255, 255];
+        // Full-height first bar, half-height second bar, gaps between them.
+        assert_ne!(pixel(0, 0), white);
+        assert_eq!(pixel(BAR_PITCH - 1, height - 1), white);
+        assert_eq!(pixel(BAR_PITCH, height / 2 - 1), white);
+        assert_ne!(pixel(BAR_PITCH, height / 2), white);
+    }
+
+    #[test]
+    fn test_histogram_sparse() {
+        // Dense equivalent: [0, -2, 0, 0, 0, 4, 0, 0, 0, 0], with buckets of width 2 starting at
+        // -2. The eight implied zeros land in the middle bucket, `[0, 2)`, and the maximum in the
+        // overflow bucket.
+        let summ = SummaryBuilder::new()
+            .histogram_sparse("grads", &[1, 5], &[-2.0, 4.0], 10, 3)
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!((histo.min, histo.max), (-2.0, 4.0));
+        assert_eq!(histo.bucket_limit, vec![0.0, 2.0, 4.0, f64::INFINITY]);
+        assert_eq!(histo.bucket, vec![1.0, 8.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 4 out of bounds for length 4")]
+    fn test_histogram_sparse_out_of_bounds() {
+        SummaryBuilder::new().histogram_sparse("h", &[0, 4], &[1.0, 2.0], 4, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "index 1 appears more than once")]
+    fn test_histogram_sparse_duplicate_index() {
+        SummaryBuilder::new().histogram_sparse("h", &[1, 0, 1], &[1.0, 2.0, 3.0], 4, 2);
+    }
+
+    #[test]
+    fn test_histogram_sparse_all_zeros() {
+        // Every value is an implied zero, so the range is just `[0, 0]`.
+        let summ = SummaryBuilder::new()
+            .histogram_sparse("grads", &[], &[], 5, 3)
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!((histo.min, histo.max, histo.num), (0.0, 0.0, 5.0));
+        assert_eq!(histo.bucket_limit, vec![f64::INFINITY]);
+        assert_eq!(histo.bucket, vec![5.0]);
+    }
+
+    #[test]
+    fn test_histogram_sparse_range_includes_zero() {
+        // All explicit values are positive, but the range extends down to the implied zeros.
+        let summ = SummaryBuilder::new()
+            .histogram_sparse("grads", &[0, 1], &[2.0, 4.0], 4, 2)
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!((histo.min, histo.max), (0.0, 4.0));
+        assert_eq!(histo.bucket, vec![2.0, 1.0, 1.0]);
+
+        // With no implied zeros, the range is just that of the values.
+        let summ = SummaryBuilder::new()
+            .histogram_sparse("grads", &[0, 1], &[2.0, 4.0], 2, 2)
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!((histo.min, histo.max), (2.0, 4.0));
+        assert_eq!(histo.bucket, vec![1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_image_list_with_captions() {
+        let images: [&[u8]; 2] = [b"png one", b"png two"];
+        let summ = SummaryBuilder::new()
+            .image_list("samples", 28, 14, &images, Some(&["a cat", "a dog"]))
+            .unwrap()
+            .build();
+        let value = &summ.value[0];
+        assert_eq!(text_of(value), vec!["28", "14", "png one", "png two"]);
+        let meta = value.metadata.as_ref().unwrap();
+        assert_eq!(meta.plugin_data.as_ref().unwrap().plugin_name, "images");
+        assert_eq!(meta.data_class, pb::DataClass::BlobSequence as i32);
+        assert_eq!(meta.summary_description, "1. a cat\n2. a dog\n");
+    }
+
+    #[test]
+    fn test_image_list_caption_mismatch() {
+        let images: [&[u8]; 2] = [b"png one", b"png two"];
+        let err = SummaryBuilder::new()
+            .image_list("samples", 28, 14, &images, Some(&["a cat"]))
+            .err()
+            .unwrap();
+        assert_eq!(err.what, "captions");
+
+        let summ = SummaryBuilder::new()
+            .image_list("samples", 28, 14, &images, None)
+            .unwrap()
+            .build();
+        assert_eq!(
+            summ.value[0].metadata.as_ref().unwrap().summary_description,
+            ""
+        );
+    }
+
+    #[test]
+    fn test_histogram_overflow_bucket() {
+        let summ = SummaryBuilder::new()
+            .histogram("h", 4, &[0.0, 1.0, 2.0, 3.0, 3.5, 4.0, 4.0])
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!(histo.bucket_limit, vec![1.0, 2.0, 3.0, 4.0, f64::INFINITY]);
+        // Left edges are inclusive and right edges exclusive, so both 4.0s overflow.
+        assert_eq!(histo.bucket, vec![1.0, 1.0, 1.0, 2.0, 2.0]);
+    }
+
+    #[test]
+    fn test_histogram_rounded() {
+        // Bucket width 0.1 accumulates floating-point error: 0.1 * 3 != 0.3.
+        let values = [0.0, 0.25, 0.5, 0.75, 1.0];
+        let summ = SummaryBuilder::new()
+            .histogram_rounded("h", 10, &values, 2)
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!(
+            histo.bucket_limit,
+            vec![
+                0.1,
+                0.2,
+                0.3,
+                0.4,
+                0.5,
+                0.6,
+                0.7,
+                0.8,
+                0.9,
+                1.0,
+                f64::INFINITY
+            ]
+        );
+        assert_eq!(histo.bucket.iter().sum::<f64>(), 5.0);
+
+        let unrounded = SummaryBuilder::new().histogram("h", 10, &values).build();
+        assert_eq!(histo.bucket, histo_of(&unrounded.value[0]).bucket);
+    }
+
+    #[test]
+    fn test_histogram_rounded_merges_edges() {
+        // Unrounded limits are 0.64, 1.08, 1.52, 1.96, and 2.4, which round to 1, 1, 2, 2, and 2.
+        let values = [0.2, 0.6, 1.4, 1.6, 2.4];
+        let summ = SummaryBuilder::new()
+            .histogram_rounded("h", 5, &values, 0)
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!(histo.bucket_limit, vec![1.0, 2.0, f64::INFINITY]);
+        assert_eq!(histo.bucket, vec![2.0, 2.0, 1.0]);
+        assert_eq!((histo.min, histo.max), (0.0, 2.0));
+
+        let empty = SummaryBuilder::new()
+            .histogram_rounded::<f64>("h", 5, &[], 0)
+            .build();
+        assert_eq!(histo_of(&empty.value[0]), &pb::HistogramProto::default());
+    }
+
+    #[test]
+    fn test_round_to() {
+        assert_eq!(round_to(1.23456, 2), 1.23);
+        assert_eq!(round_to(-1.235001, 2), -1.24);
+        assert_eq!(round_to(1e300, 10), 1e300);
+        assert_eq!(round_to(f64::INFINITY, 3), f64::INFINITY);
+    }
+
+    #[test]
+    fn test_scalar_annotated() {
+        let summ = SummaryBuilder::new()
+            .scalar_annotated("lr", 0.01, "LR dropped here")
+            .build();
+        assert_eq!(summ.value.len(), 2);
+        assert_eq!(summ.value[0].tag, "lr");
+        assert_eq!(summ.value[0].value, Some(InnerValue::SimpleValue(0.01)));
+        assert_eq!(summ.value[1].tag, "lr/notes");
+        assert_eq!(text_of(&summ.value[1]), vec!["LR dropped here"]);
+    }
+
+    #[test]
+    fn test_histogram_known_range() {
+        let values = [0.0f32, 0.1, 0.4, 0.5, 0.9, 1.0];
+        let summ = SummaryBuilder::new()
+            .histogram("h", 5, &values)
+            .histogram_known_range("h", 5, 0.0, 1.0, &values)
+            .build();
+        assert_eq!(summ.value[0], summ.value[1]);
+    }
+
+    #[test]
+    fn test_histogram_known_range_out_of_range() {
+        let summ = SummaryBuilder::new()
+            .histogram_known_range("h", 2, 0.0, 1.0, &[-5.0, 0.25, 0.75, 5.0])
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!((histo.min, histo.max), (0.0, 1.0));
+        assert_eq!(histo.bucket_limit, vec![0.5, 1.0, f64::INFINITY]);
+        assert_eq!(histo.bucket, vec![2.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_histogram_with_limits() {
+        let summ = SummaryBuilder::new()
+            .histogram_with_limits(
+                "h",
+                &[0.0, 1.0, 10.0],
+                &[-5.0, 0.0, 0.5, 1.0, 9.0, 10.0, 50.0],
+            )
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!(histo.bucket_limit, vec![0.0, 1.0, 10.0, f64::INFINITY]);
+        assert_eq!(histo.bucket, vec![1.0, 2.0, 2.0, 2.0]);
+        assert_eq!((histo.min, histo.max), (-5.0, 50.0));
+        assert_eq!(histo.num, 7.0);
+        assert_eq!(histo.sum, 65.5);
+
+        // An infinite last limit is the overflow bucket already.
+        let summ = SummaryBuilder::new()
+            .histogram_with_limits("h", &[0.0, f64::INFINITY], &[1.0f32])
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!(histo.bucket_limit, vec![0.0, f64::INFINITY]);
+        assert_eq!(histo.bucket, vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_histogram_accumulator() {
+        let values = [-5.0, 0.0, 0.5, 1.0, 9.0, 10.0, 50.0];
+        let mut acc = HistogramAccumulator::new(&[0.0, 1.0, 10.0]);
+        acc.add(values[0]);
+        acc.add_slice(&values[1..4]);
+        acc.add_slice(&values[4..]);
+        let expected = SummaryBuilder::new()
+            .histogram_with_limits("h", &[0.0, 1.0, 10.0], &values)
+            .build();
+        assert_eq!(&acc.finish(), histo_of(&expected.value[0]));
+
+        let empty = HistogramAccumulator::new(&[1.0]).finish();
+        assert_eq!(empty.bucket, vec![0.0, 0.0]);
+        assert_eq!((empty.num, empty.min, empty.max), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_histogram_default_buckets() {
+        let limits = default_bucket_limits();
+        assert_eq!(limits.len(), 1551);
+        assert_eq!(limits[775], 0.0);
+        assert_eq!(limits[776], 1e-12);
+        assert_eq!(limits[limits.len() - 1], f64::MAX);
+        assert!(limits.windows(2).all(|w| w[0] < w[1]));
+
+        let summ = SummaryBuilder::new()
+            .histogram_default_buckets("h", &[0.0, 0.0, 1.0])
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        // Zeros go in `[0, 1e-12)`, and each run of empty buckets around it collapses into one.
+        let one = limits.partition_point(|&l| l <= 1.0);
+        let expected_limits = vec![0.0, 1e-12, limits[one - 1], limits[one], f64::MAX];
+        assert_eq!(histo.bucket_limit, expected_limits);
+        assert_eq!(histo.bucket, vec![0.0, 2.0, 0.0, 1.0, 0.0]);
+        assert_eq!((histo.min, histo.max, histo.num), (0.0, 1.0, 3.0));
+
+        let summ = SummaryBuilder::new()
+            .histogram_default_buckets::<f64>("empty", &[])
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!(histo.bucket_limit, vec![f64::MAX]);
+        assert_eq!(histo.bucket, vec![0.0]);
+    }
+
+    #[test]
+    fn test_histogram_uniform_counts() {
+        let summ = SummaryBuilder::new()
+            .histogram_uniform_counts("h", -1.0, 1.0, &[3, 0, 5, 2])
+            .histogram_uniform_counts("empty", 0.0, 1.0, &[])
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!((histo.min, histo.max), (-1.0, 1.0));
+        assert_eq!(histo.bucket_limit, vec![-0.5, 0.0, 0.5, 1.0]);
+        assert_eq!(histo.bucket, vec![3.0, 0.0, 5.0, 2.0]);
+        assert_eq!(histo.num, 10.0);
+        let empty = histo_of(&summ.value[1]);
+        assert!(empty.bucket.is_empty() && empty.bucket_limit.is_empty());
+    }
+
+    #[test]
+    fn test_histograms() {
+        let weights = [0.0, 1.0, 2.0];
+        let biases = [-1.0, 1.0];
+        let summ = SummaryBuilder::new()
+            .histograms(4, &[("w/0", &weights), ("b/0", &biases), ("empty", &[])])
+            .build();
+        let tags: Vec<&str> = summ.value.iter().map(|v| v.tag.as_str()).collect();
+        assert_eq!(tags, vec!["w/0", "b/0", "empty"]);
+        let expected = SummaryBuilder::new()
+            .histogram("w/0", 4, &weights)
+            .histogram("b/0", 4, &biases)
+            .histogram("empty", 4, &[] as &[f64])
+            .build();
+        assert_eq!(summ, expected);
+    }
+
+    #[test]
+    fn test_scalar_f64() {
+        let summ = SummaryBuilder::new()
+            .scalar_f64("loss", 1.0 + 1e-12)
+            .build();
+        let value = &summ.value[0];
+        match &value.value {
+            Some(InnerValue::Tensor(t)) => {
+                assert_eq!(t.dtype, pb::DataType::DtDouble as i32);
+                assert_eq!(t.tensor_shape, Some(pb::TensorShapeProto::default()));
+                assert_eq!(t.double_val, vec![1.0 + 1e-12]);
+            }
+            other => panic!("{:?}", other),
+        }
+        let meta = value.metadata.as_ref().unwrap();
+        assert_eq!(meta.data_class, pb::DataClass::Scalar as i32);
+        assert_eq!(meta.plugin_data.as_ref().unwrap().plugin_name, "scalars");
+    }
+
+    #[test]
+    fn test_extend() {
+        let model = SummaryBuilder::new().scalar("loss", 0.5).build();
+        let data = SummaryBuilder::new()
+            .scalar("queue_len", 3.0)
+            .text("note", &"hi")
+            .build();
+        let summ = SummaryBuilder::new()
+            .scalar("lr", 0.1)
+            .extend(model)
+            .extend(data)
+            .build();
+        let tags: Vec<&str> = summ.value.iter().map(|v| v.tag.as_str()).collect();
+        assert_eq!(tags, vec!["lr", "loss", "queue_len", "note"]);
+    }
+
+    #[test]
+    fn test_remove() {
+        let summ = SummaryBuilder::new()
+            .scalar("loss", 1.0)
+            .scalar("lr", 0.1)
+            .scalar("loss", 2.0)
+            .remove("loss")
+            .remove("missing")
+            .scalar("loss", 0.5)
+            .build();
+        let tags: Vec<&str> = summ.value.iter().map(|v| v.tag.as_str()).collect();
+        assert_eq!(tags, vec!["lr", "loss"]);
+        assert_eq!(summ.value[1].value, Some(InnerValue::SimpleValue(0.5)));
+    }
+
+    #[test]
+    fn test_histogram_raw() {
+        let summ = SummaryBuilder::new()
+            .histogram_raw(
+                "h",
+                -1.0,
+                3.0,
+                4.0,
+                5.0,
+                11.0,
+                &[0.0, 2.0, f64::INFINITY],
+                &[1.0, 2.0, 1.0],
+            )
+            .build();
+        let expected = pb::HistogramProto {
+            min: -1.0,
+            max: 3.0,
+            num: 4.0,
+            sum: 5.0,
+            sum_squares: 11.0,
+            bucket_limit: vec![0.0, 2.0, f64::INFINITY],
+            bucket: vec![1.0, 2.0, 1.0],
+        };
+        assert_eq!(histo_of(&summ.value[0]), &expected);
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "same length"))]
+    fn test_histogram_raw_mismatch() {
+        SummaryBuilder::new().histogram_raw("h", 0.0, 1.0, 1.0, 1.0, 1.0, &[1.0], &[]);
+    }
+
+    #[test]
+    fn test_scalar_described() {
+        let summ = SummaryBuilder::new()
+            .scalar_described("loss", 0.5, "Training loss", "Mean *cross-entropy*.")
+            .scalar("lr", 0.1)
+            .build();
+        assert_eq!(summ.value[0].value, Some(InnerValue::SimpleValue(0.5)));
+        let meta = summ.value[0].metadata.as_ref().unwrap();
+        assert_eq!(meta.display_name, "Training loss");
+        assert_eq!(meta.summary_description, "Mean *cross-entropy*.");
+        assert_eq!(summ.value[1].metadata, None);
+    }
+
+    #[test]
+    fn test_describe_keeps_plugin_data() {
+        let summ = SummaryBuilder::new()
+            .scalar_f64("x", 1.0)
+            .describe("X", "An x.")
+            .build();
+        let meta = summ.value[0].metadata.as_ref().unwrap();
+        assert_eq!(
+            meta.plugin_data,
+            scalar_metadata().plugin_data,
+            "plugin data should be kept"
+        );
+        assert_eq!(meta.display_name, "X");
+        assert_eq!(meta.summary_description, "An x.");
+    }
+
+    #[test]
+    fn test_scalar_i64() {
+        let big = (1 << 24) + 1;
+        let summ = SummaryBuilder::new().scalar_i64("examples", big).build();
+        let value = &summ.value[0];
+        match &value.value {
+            Some(InnerValue::Tensor(t)) => {
+                assert_eq!(t.dtype, pb::DataType::DtInt64 as i32);
+                assert_eq!(t.tensor_shape, Some(pb::TensorShapeProto::default()));
+                assert_eq!(t.int64_val, vec![big]);
+            }
+            other => panic!("{:?}", other),
+        }
+        assert_eq!(value.metadata, Some(scalar_metadata()));
+    }
+
+    #[test]
+    fn test_histogram_moments() {
+        let summ = SummaryBuilder::new()
+            .histogram("h", 3, &[1.0, 2.0, 3.0, 6.0])
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!(histo.num, 4.0);
+        assert_eq!(histo.sum, 12.0);
+        assert_eq!(histo.sum_squares, 50.0);
+    }
+
+    #[test]
+    fn test_histogram_moments_large_offset() {
+        // Values `1e6 + 0.01 * k` for `k` in `0..n` have variance `0.01^2 * (n^2 - 1) / 12`.
+        let n = 10_000;
+        let values: Vec<f64> = (0..n).map(|k| 1e6 + 0.01 * k as f64).collect();
+        let summ = SummaryBuilder::new().histogram("h", 10, &values).build();
+        let histo = histo_of(&summ.value[0]);
+        let mean = histo.sum / histo.num;
+        let variance = histo.sum_squares / histo.num - mean * mean;
+        let n = n as f64;
+        let expected = 1e-4 * (n * n - 1.0) / 12.0;
+        assert!(
+            ((variance - expected) / expected).abs() < 1e-6,
+            "variance {} vs. expected {}",
+            variance,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_histogram_sparse_moments() {
+        let summ = SummaryBuilder::new()
+            .histogram_sparse("h", &[1, 3], &[2.0, -1.0], 5, 2)
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!((histo.num, histo.sum, histo.sum_squares), (5.0, 1.0, 5.0));
+    }
+
+    #[test]
+    fn test_histogram_with_stats() {
+        let values = [2.0f32, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        let summ = SummaryBuilder::new()
+            .histogram_with_stats("w", 4, &values)
+            .build();
+        assert_eq!(
+            summ.value[0],
+            SummaryBuilder::new()
+                .histogram("w", 4, &values)
+                .build()
+                .value[0]
+        );
+        let scalars: Vec<(&str, Option<InnerValue>)> = summ.value[1..]
+            .iter()
+            .map(|v| (v.tag.as_str(), v.value.clone()))
+            .collect();
+        let scalar = |tag, x| (tag, Some(InnerValue::SimpleValue(x)));
+        assert_eq!(
+            scalars,
+            vec![
+                scalar("w/mean", 5.0),
+                scalar("w/std", 2.0),
+                scalar("w/min", 2.0),
+                scalar("w/max", 9.0),
+            ]
+        );
+
+        let empty = SummaryBuilder::new()
+            .histogram_with_stats("e", 4, &[] as &[f64])
+            .build();
+        assert_eq!(empty.value.len(), 1);
+    }
+
+    #[test]
+    fn test_histogram_last_edge_is_max() {
+        // Here, `min + 38 * ((max - min) / 38)` is `6.949999999999999`, just below `max`.
+        let (min, max) = (2.03, 6.95);
+        let below_max = 6.949999999999999;
+        let summ = SummaryBuilder::new()
+            .histogram("h", 38, &[min, below_max, max])
+            .build();
+        let histo = histo_of(&summ.value[0]);
+        assert_eq!(histo.bucket_limit[37], max);
+        assert_eq!(histo.bucket[0], 1.0);
+        assert_eq!(histo.bucket[37], 1.0);
+        assert_eq!(histo.bucket[38], 1.0);
+        for (i, pair) in histo.bucket_limit.windows(2).enumerate() {
+            assert!(pair[0] < pair[1], "limits {} and {}: {:?}", i, i + 1, pair);
+        }
+    }
+
+    #[test]
+    fn test_gradient_norm() {
+        let summ = SummaryBuilder::new()
+            .gradient_norm("grad", &[3.0, 4.0], 5.0)
+            .gradient_norm("big", &[3.0, 4.0, 12.0], 5.0)
+            .build();
+        let values: Vec<(&str, Option<InnerValue>)> = summ
+            .value
+            .iter()
+            .map(|v| (v.tag.as_str(), v.value.clone()))
+            .collect();
+        let scalar = |tag, x| (tag, Some(InnerValue::SimpleValue(x)));
+        assert_eq!(
+            values,
+            vec![
+                scalar("grad/global_norm", 5.0),
+                scalar("grad/clipped", 0.0),
+                scalar("big/global_norm", 13.0),
+                scalar("big/clipped", 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_table() {
+        let strings = |xs: &[&str]| -> Vec<String> { xs.iter().map(|x| x.to_string()).collect() };
+        let train = strings(&["0.1", "0.9"]);
+        let eval = strings(&["0.3", "a|b"]);
+        let summ = SummaryBuilder::new()
+            .text_table("t", &["loss", "acc"], &["train", "eval"], &[&train, &eval])
+            .unwrap()
+            .build();
+        let text = &text_of(&summ.value[0])[0];
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "|  | loss | acc |",
+                "| --- | --- | --- |",
+                "| **train** | 0.1 | 0.9 |",
+                "| **eval** | 0.3 | a\\|b |",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comparison_table() {
+        let summ = SummaryBuilder::new()
+            .comparison_table(
+                "sweep",
+                &[
+                    ("lr=0.1", &[("loss", 0.5), ("acc", 0.75)]),
+                    ("lr=0.01", &[("acc", 0.8), ("f1", 0.25)]),
+                ],
+            )
+            .build();
+        let text = &text_of(&summ.value[0])[0];
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "| Run | loss | acc | f1 |",
+                "| --- | --- | --- | --- |",
+                "| lr=0.1 | 0.5 | 0.75 |  |",
+                "| lr=0.01 |  | 0.8 | 0.25 |",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_text_table_mismatch() {
+        let row = vec!["1".to_string()];
+        let err = SummaryBuilder::new()
+            .text_table("t", &["a", "b"], &["r"], &[&row])
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            LengthMismatch {
+                what: "cells in a row",
+                got: 1,
+                want: 2
+            }
+        );
+        let err = SummaryBuilder::new()
+            .text_table("t", &["a"], &["r", "s"], &[&row])
+            .err()
+            .unwrap();
+        assert_eq!((err.what, err.got, err.want), ("rows", 1, 2));
+    }
+
+    #[test]
+    fn test_tensor_bool() {
+        let mask = [true, false, false, true, true, false];
+        let summ = SummaryBuilder::new()
+            .tensor_bool("mask", &mask, &[2, 3])
+            .build();
+        let tensor = match &summ.value[0].value {
+            Some(InnerValue::Tensor(t)) => t,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(tensor.dtype, pb::DataType::DtBool as i32);
+        assert_eq!(tensor.tensor_shape, Some(tensor_shape(&[2, 3])));
+        assert_eq!(tensor.bool_val, mask);
+        assert_eq!(summ.value[0].metadata, None);
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "bad shape"))]
+    fn test_tensor_bool_bad_shape() {
+        SummaryBuilder::new().tensor_bool("mask", &[true; 5], &[2, 3]);
+    }
+
+    #[test]
+    fn test_tensor_numeric() {
+        let summ = SummaryBuilder::new()
+            .tensor_f32("f32", &[1.5, 2.5], &[2], "p")
+            .tensor_f64("f64", &[0.25], &[], "p")
+            .tensor_i32("i32", &[1, 2, 3, 4, 5, 6], &[3, 2], "p")
+            .tensor_i64("i64", &[-1], &[1, 1], "q")
+            .build();
+        let tensors: Vec<&pb::TensorProto> = summ
+            .value
+            .iter()
+            .map(|v| match &v.value {
+                Some(InnerValue::Tensor(t)) => t,
+                other => panic!("{:?}", other),
+            })
+            .collect();
+        assert_eq!(tensors[0].dtype, pb::DataType::DtFloat as i32);
+        assert_eq!(tensors[0].float_val, vec![1.5, 2.5]);
+        assert_eq!(tensors[1].dtype, pb::DataType::DtDouble as i32);
+        assert_eq!(tensors[1].tensor_shape, Some(tensor_shape(&[])));
+        assert_eq!(tensors[1].double_val, vec![0.25]);
+        assert_eq!(tensors[2].dtype, pb::DataType::DtInt32 as i32);
+        assert_eq!(tensors[2].tensor_shape, Some(tensor_shape(&[3, 2])));
+        assert_eq!(tensors[2].int_val, vec![1, 2, 3, 4, 5, 6]);
+        assert_eq!(tensors[3].dtype, pb::DataType::DtInt64 as i32);
+        assert_eq!(tensors[3].int64_val, vec![-1]);
+        let plugin_data = summ.value[3]
+            .metadata
+            .as_ref()
+            .unwrap()
+            .plugin_data
+            .as_ref();
+        assert_eq!(plugin_data.unwrap().plugin_name, "q");
+    }
+
+    #[test]
+    fn test_tensor_with_plugin() {
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtString.into(),
+            tensor_shape: Some(tensor_shape(&[1])),
+            string_val: vec![b"blob".to_vec().into()],
+            ..Default::default()
+        };
+        let summ = SummaryBuilder::new()
+            .tensor_with_plugin("x", tensor.clone(), "my_plugin", b"\x08\x01".to_vec())
+            .build();
+        let value = &summ.value[0];
+        assert_eq!(value.value, Some(InnerValue::Tensor(tensor)));
+        let meta = value.metadata.as_ref().unwrap();
+        assert_eq!(meta.data_class, pb::DataClass::Tensor as i32);
+        let plugin_data = meta.plugin_data.as_ref().unwrap();
+        assert_eq!(plugin_data.plugin_name, "my_plugin");
+        assert_eq!(&plugin_data.content[..], b"\x08\x01");
+    }
+
+    #[test]
+    fn test_tensor_bytes() {
+        let blobs: [&[u8]; 2] = [b"\x00\xff", b""];
+        let summ = SummaryBuilder::new()
+            .tensor_bytes("x", &blobs, &[2], "my_plugin")
+            .build();
+        let value = &summ.value[0];
+        let expected = pb::TensorProto {
+            dtype: pb::DataType::DtString.into(),
+            tensor_shape: Some(tensor_shape(&[2])),
+            string_val: vec![b"\x00\xff".to_vec().into(), Vec::new().into()],
+            ..Default::default()
+        };
+        assert_eq!(value.value, Some(InnerValue::Tensor(expected)));
+        let meta = value.metadata.as_ref().unwrap();
+        assert_eq!(meta.data_class, pb::DataClass::Tensor as i32);
+        assert_eq!(meta.plugin_data.as_ref().unwrap().plugin_name, "my_plugin");
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "bad shape"))]
+    fn test_tensor_numeric_bad_shape() {
+        SummaryBuilder::new().tensor_i64("x", &[1, 2, 3], &[2, 2], "p");
+    }
+
+    #[test]
+    fn test_confusion_tensor() {
+        let summ = SummaryBuilder::new()
+            .confusion_tensor("confusion", &[&[5, 1], &[2, 7]])
+            .build();
+        let value = &summ.value[0];
+        let tensor = match &value.value {
+            Some(InnerValue::Tensor(t)) => t,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(tensor.dtype, pb::DataType::DtInt64 as i32);
+        assert_eq!(tensor.tensor_shape, Some(tensor_shape(&[2, 2])));
+        assert_eq!(tensor.int64_val, vec![5, 1, 2, 7]);
+        let plugin_data = value.metadata.as_ref().unwrap().plugin_data.as_ref();
+        assert_eq!(
+            plugin_data.unwrap().plugin_name,
+            CONFUSION_MATRIX_PLUGIN_NAME
+        );
+    }
+
+    #[test]
+    fn test_audio() {
+        let wav = b"RIFF\x24\x00\x00\x00WAVEfmt ";
+        let summ = SummaryBuilder::new()
+            .audio("speech", wav, 16000.0, 1, 8000)
+            .build();
+        let value = &summ.value[0];
+        assert_eq!(
+            value.value,
+            Some(InnerValue::Audio(pb::summary::Audio {
+                sample_rate: 16000.0,
+                num_channels: 1,
+                length_frames: 8000,
+                encoded_audio_string: wav.to_vec().into(),
+                content_type: "audio/wav".to_string(),
+            }))
+        );
+        let plugin_data = value
+            .metadata
+            .as_ref()
+            .unwrap()
+            .plugin_data
+            .as_ref()
+            .unwrap();
+        assert_eq!(plugin_data.plugin_name, "audio");
+        let content = <pb::AudioPluginData as prost::Message>::decode(&plugin_data.content[..]);
+        assert_eq!(
+            content.unwrap().encoding,
+            pb::audio_plugin_data::Encoding::Wav as i32
+        );
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "bad channel count"))]
+    fn test_audio_bad_channels() {
+        SummaryBuilder::new().audio("speech", b"", 16000.0, 0, 0);
+    }
+
+    #[test]
+    fn test_model_stats() {
+        let big = (1u64 << 53) + 1; // not representable as an `f32` or `f64`
+        let summ = SummaryBuilder::new()
+            .model_stats(big, 1_000_000, None)
+            .model_stats(u64::MAX, 0, Some(12_345_678_901))
+            .build();
+        let values: Vec<(&str, i64)> = summ
+            .value
+            .iter()
+            .map(|v| {
+                let tensor = match &v.value {
+                    Some(InnerValue::Tensor(t)) => t,
+                    other => panic!("{:?}", other),
+                };
+                assert_eq!(tensor.dtype, pb::DataType::DtInt64 as i32);
+                assert_eq!(tensor.tensor_shape, Some(tensor_shape(&[])));
+                let meta = v.metadata.as_ref().unwrap();
+                assert_eq!(meta.plugin_data.as_ref().unwrap().plugin_name, "scalars");
+                assert_eq!(meta.data_class, pb::DataClass::Scalar as i32);
+                (v.tag.as_str(), tensor.int64_val[0])
+            })
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                ("model/total_params", big as i64),
+                ("model/trainable_params", 1_000_000),
+                ("model/total_params", i64::MAX),
+                ("model/trainable_params", 0),
+                ("model/flops", 12_345_678_901),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pr_curve() {
+        let summ = SummaryBuilder::new()
+            .pr_curve(
+                "pr",
+                &[4.0, 2.0],
+                &[4.0, 1.0],
+                &[0.0, 3.0],
+                &[0.0, 2.0],
+                &[0.5, 2.0 / 3.0],
+                &[1.0, 0.5],
+            )
+            .build();
+        let value = &summ.value[0];
+        let tensor = match &value.value {
+            Some(InnerValue::Tensor(t)) => t,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(tensor.dtype, pb::DataType::DtFloat as i32);
+        assert_eq!(tensor.tensor_shape, Some(tensor_shape(&[6, 2])));
+        assert_eq!(
+            tensor.float_val,
+            vec![
+                4.0,
+                2.0,
+                4.0,
+                1.0,
+                0.0,
+                3.0,
+                0.0,
+                2.0,
+                0.5,
+                2.0 / 3.0,
+                1.0,
+                0.5
+            ]
+        );
+        let plugin_data = value
+            .metadata
+            .as_ref()
+            .unwrap()
+            .plugin_data
+            .as_ref()
+            .unwrap();
+        assert_eq!(plugin_data.plugin_name, "pr_curves");
+        let content =
+            <pb::PrCurvePluginData as prost::Message>::decode(&plugin_data.content[..]).unwrap();
+        assert_eq!(content.num_thresholds, 2);
+    }
+
+    #[test]
+    #[cfg_attr(debug_assertions, should_panic(expected = "different lengths"))]
+    fn test_pr_curve_length_mismatch() {
+        SummaryBuilder::new().pr_curve("pr", &[1.0], &[1.0], &[1.0], &[1.0], &[1.0], &[]);
+    }
+
+    #[test]
+    fn test_mesh() {
+        use pb::mesh_plugin_data::ContentType;
+        let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let colors = [[255, 0, 0], [0, 255, 0], [0, 0, 255]];
+        let summ = SummaryBuilder::new()
+            .mesh("cloud", &vertices, None, Some(&colors))
+            .build();
+        let tags: Vec<&str> = summ.value.iter().map(|v| v.tag.as_str()).collect();
+        assert_eq!(tags, vec!["cloud_VERTEX", "cloud_COLOR"]);
+        let contents: Vec<pb::MeshPluginData> = summ
+            .value
+            .iter()
+            .map(|v| {
+                let plugin_data = v.metadata.as_ref().unwrap().plugin_data.as_ref().unwrap();
+                assert_eq!(plugin_data.plugin_name, "mesh");
+                prost::Message::decode(&plugin_data.content[..]).unwrap()
+            })
+            .collect();
+        let expected_mask = 1 << ContentType::Vertex as u32 | 1 << ContentType::Color as u32;
+        for (content, content_type) in contents
+            .iter()
+            .zip([ContentType::Vertex, ContentType::Color])
+        {
+            assert_eq!(content.name, "cloud");
+            assert_eq!(content.content_type, content_type as i32);
+            assert_eq!(content.shape, vec![1, 3, 3]);
+            assert_eq!(content.components, expected_mask);
+        }
+        let tensor = match &summ.value[1].value {
+            Some(InnerValue::Tensor(t)) => t,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(tensor.dtype, pb::DataType::DtUint8 as i32);
+        assert_eq!(tensor.tensor_shape, Some(tensor_shape(&[1, 3, 3])));
+        assert_eq!(tensor.int_val, vec![255, 0, 0, 0, 255, 0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_mesh_with_faces() {
+        let vertices = [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        let summ = SummaryBuilder::new()
+            .mesh("tri", &vertices, Some(&[[0, 1, 2]]), None)
+            .build();
+        let tags: Vec<&str> = summ.value.iter().map(|v| v.tag.as_str()).collect();
+        assert_eq!(tags, vec!["tri_VERTEX", "tri_FACE"]);
+        let tensor = match &summ.value[1].value {
+            Some(InnerValue::Tensor(t)) => t,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(tensor.tensor_shape, Some(tensor_shape(&[1, 1, 3])));
+        assert_eq!(tensor.int_val, vec![0, 1, 2]);
+    }
 }
@@ -0,0 +1,37 @@
+//! Test helper for scratch directories that are cleaned up when dropped.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A fresh, empty directory under the system temporary directory. The directory and all its
+/// contents are removed when this value is dropped.
+#[derive(Debug)]
+pub struct TempDir(PathBuf);
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+impl TempDir {
+    /// Creates a new directory with a name that is unique to this process and call.
+    pub fn create() -> Self {
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!(
+            "tensorboard-writer-test.{}.{}",
+            std::process::id(),
+            n
+        ));
+        let _ = std::fs::remove_dir_all(&path); // stale from a previous process with this PID
+        std::fs::create_dir_all(&path).expect("failed to create temp dir");
+        TempDir(path)
+    }
+
+    /// The path to the directory.
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
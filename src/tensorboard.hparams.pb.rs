@@ -0,0 +1,166 @@
+/// Contains information about an experiment: the hyperparameters and
+/// metrics that its sessions log.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Experiment {
+    /// -- Metadata --
+    /// The experiment name.
+    #[prost(string, tag="6")]
+    pub name: ::prost::alloc::string::String,
+    /// A description. May contain markdown.
+    #[prost(string, tag="1")]
+    pub description: ::prost::alloc::string::String,
+    /// An id for the owning user or group.
+    #[prost(string, tag="2")]
+    pub user: ::prost::alloc::string::String,
+    /// The time the experiment was created. In seconds since the UNIX epoch.
+    #[prost(double, tag="3")]
+    pub time_created_secs: f64,
+    /// -- Schema --
+    /// The hyperparameters used in the experiment.
+    #[prost(message, repeated, tag="4")]
+    pub hparam_infos: ::prost::alloc::vec::Vec<HParamInfo>,
+    /// The metrics recorded in the experiment.
+    #[prost(message, repeated, tag="5")]
+    pub metric_infos: ::prost::alloc::vec::Vec<MetricInfo>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HParamInfo {
+    /// An id for the hyperparameter.
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    /// A string used to display the hyperparameter.
+    #[prost(string, tag="2")]
+    pub display_name: ::prost::alloc::string::String,
+    /// A description. May contain markdown.
+    #[prost(string, tag="3")]
+    pub description: ::prost::alloc::string::String,
+    /// The data type of this hyperparameter.
+    #[prost(enumeration="DataType", tag="4")]
+    pub r#type: i32,
+    /// Whether the hyperparameter's value differs across sessions.
+    #[prost(bool, tag="7")]
+    pub differs: bool,
+    /// The domain of the hyperparameter: either a finite set of values, or
+    /// an interval of real numbers.
+    #[prost(oneof="h_param_info::Domain", tags="5, 6")]
+    pub domain: ::core::option::Option<h_param_info::Domain>,
+}
+/// Nested message and enum types in `HParamInfo`.
+pub mod h_param_info {
+    /// The domain of the hyperparameter: either a finite set of values, or
+    /// an interval of real numbers.
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Domain {
+        /// A discrete set of the values this hyperparameter can hold.
+        #[prost(message, tag="5")]
+        DomainDiscrete(super::super::super::google::protobuf::ListValue),
+        /// Numeric data type only. The (real) interval from which values of this
+        /// hyperparameter are taken.
+        #[prost(message, tag="6")]
+        DomainInterval(super::Interval),
+    }
+}
+/// Represents the closed interval [min_value, max_value] of the real line.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Interval {
+    #[prost(double, tag="1")]
+    pub min_value: f64,
+    #[prost(double, tag="2")]
+    pub max_value: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MetricName {
+    /// An identifier for a metric. A metric is a real-valued function of the
+    /// model. The group is the relative path of the run directory under the
+    /// session directory, and the tag is the tag of the scalar summary.
+    #[prost(string, tag="1")]
+    pub group: ::prost::alloc::string::String,
+    #[prost(string, tag="2")]
+    pub tag: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MetricInfo {
+    #[prost(message, optional, tag="1")]
+    pub name: ::core::option::Option<MetricName>,
+    #[prost(string, tag="3")]
+    pub display_name: ::prost::alloc::string::String,
+    #[prost(string, tag="4")]
+    pub description: ::prost::alloc::string::String,
+    #[prost(enumeration="DatasetType", tag="5")]
+    pub dataset_type: i32,
+}
+/// HParams summaries created by the `tensorboard.plugins.hparams.summary`
+/// module will include `SummaryMetadata` whose `plugin_data` field has
+/// as `content` a binary string that is the encoding of an
+/// `HParamsPluginData` proto.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HParamsPluginData {
+    /// The version of the plugin data schema.
+    #[prost(int32, tag="1")]
+    pub version: i32,
+    #[prost(oneof="h_params_plugin_data::Data", tags="2, 3, 4")]
+    pub data: ::core::option::Option<h_params_plugin_data::Data>,
+}
+/// Nested message and enum types in `HParamsPluginData`.
+pub mod h_params_plugin_data {
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Data {
+        #[prost(message, tag="2")]
+        Experiment(super::Experiment),
+        #[prost(message, tag="3")]
+        SessionStartInfo(super::SessionStartInfo),
+        #[prost(message, tag="4")]
+        SessionEndInfo(super::SessionEndInfo),
+    }
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SessionStartInfo {
+    /// A map describing the hyperparameter values for the session.
+    /// Maps each hyperparameter name to its value.
+    #[prost(map="string, message", tag="1")]
+    pub hparams: ::std::collections::HashMap<::prost::alloc::string::String, super::super::google::protobuf::Value>,
+    /// A URI for a resource that will be associated with this session.
+    #[prost(string, tag="2")]
+    pub model_uri: ::prost::alloc::string::String,
+    /// A URL for a monitoring UI.
+    #[prost(string, tag="3")]
+    pub monitor_url: ::prost::alloc::string::String,
+    /// An identifier for a session group. Sessions with the same group name
+    /// are considered repetitions of each other.
+    #[prost(string, tag="4")]
+    pub group_name: ::prost::alloc::string::String,
+    /// The time the session started, in seconds since the UNIX epoch.
+    #[prost(double, tag="5")]
+    pub start_time_secs: f64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SessionEndInfo {
+    #[prost(enumeration="Status", tag="1")]
+    pub status: i32,
+    /// The time the session ended, in seconds since the UNIX epoch.
+    #[prost(double, tag="2")]
+    pub end_time_secs: f64,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DataType {
+    Unset = 0,
+    String = 1,
+    Bool = 2,
+    Float64 = 3,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum DatasetType {
+    DatasetUnknown = 0,
+    DatasetTraining = 1,
+    DatasetValidation = 2,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Status {
+    Unknown = 0,
+    Success = 1,
+    Failure = 2,
+    Running = 3,
+}
@@ -661,3 +661,334 @@ pub struct ImagePluginData {
     #[prost(bool, tag="2")]
     pub converted_to_tensor: bool,
 }
+/// PR curve summaries created by the `tensorboard.plugins.pr_curve.summary`
+/// module will include `SummaryMetadata` whose `plugin_data` field has
+/// as `content` a binary string that is the encoding of a
+/// `PrCurvePluginData` proto.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PrCurvePluginData {
+    /// Version `0` is the only supported version.
+    #[prost(int32, tag="1")]
+    pub version: i32,
+    #[prost(uint32, tag="2")]
+    pub num_thresholds: u32,
+}
+/// Encapsulates information on a single chart. Many charts appear in a category.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Chart {
+    /// The title shown atop this chart. Optional. Defaults to 'untitled'.
+    #[prost(string, tag="1")]
+    pub title: ::prost::alloc::string::String,
+    /// The content of the chart. This depends on the type of the chart.
+    #[prost(oneof="chart::Content", tags="2, 3")]
+    pub content: ::core::option::Option<chart::Content>,
+}
+/// Nested message and enum types in `Chart`.
+pub mod chart {
+    /// The content of the chart. This depends on the type of the chart.
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Content {
+        #[prost(message, tag="2")]
+        Multiline(super::MultilineChartContent),
+        #[prost(message, tag="3")]
+        Margin(super::MarginChartContent),
+    }
+}
+/// Encapsulates information on a single line chart. This line chart may have
+/// lines associated with several tags.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MultilineChartContent {
+    /// A list of regular expressions for tags that should appear in this chart.
+    /// Tags are matched from beginning to end. Each regex captures a set of tags.
+    #[prost(string, repeated, tag="1")]
+    pub tag: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+/// Encapsulates information on a single margin chart. A margin chart uses fill
+/// area to visualize lower and upper bounds that surround a value.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MarginChartContent {
+    /// A list of data series to include within this margin chart.
+    #[prost(message, repeated, tag="1")]
+    pub series: ::prost::alloc::vec::Vec<margin_chart_content::Series>,
+}
+/// Nested message and enum types in `MarginChartContent`.
+pub mod margin_chart_content {
+    /// Encapsulates a tag of data for the chart.
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Series {
+        /// The exact tag string associated with the scalar summaries making up the
+        /// main value between the bounds.
+        #[prost(string, tag="1")]
+        pub value: ::prost::alloc::string::String,
+        /// The exact tag string associated with the scalar summaries making up the
+        /// lower bound.
+        #[prost(string, tag="2")]
+        pub lower: ::prost::alloc::string::String,
+        /// The exact tag string associated with the scalar summaries making up the
+        /// upper bound.
+        #[prost(string, tag="3")]
+        pub upper: ::prost::alloc::string::String,
+    }
+}
+/// A category contains a group of charts. Each category maps to a collapsible
+/// within the dashboard.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Category {
+    /// This string appears atop each grouping of charts within the dashboard.
+    #[prost(string, tag="1")]
+    pub title: ::prost::alloc::string::String,
+    /// Encapsulates data on charts to be shown in the category.
+    #[prost(message, repeated, tag="2")]
+    pub chart: ::prost::alloc::vec::Vec<Chart>,
+    /// Whether this category should be initially closed. False by default.
+    #[prost(bool, tag="3")]
+    pub closed: bool,
+}
+/// A layout encapsulates how charts are laid out within the custom scalars
+/// dashboard.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Layout {
+    /// Version `0` is the only supported version.
+    #[prost(int32, tag="1")]
+    pub version: i32,
+    /// The categories here are rendered from top to bottom.
+    #[prost(message, repeated, tag="2")]
+    pub category: ::prost::alloc::vec::Vec<Category>,
+}
+/// A MeshPluginData encapsulates information on which plugins are able to make
+/// use of a certain summary value.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MeshPluginData {
+    /// Version `0` is the only supported version.
+    #[prost(int32, tag="1")]
+    pub version: i32,
+    /// The name of the mesh summary this particular summary belongs to.
+    #[prost(string, tag="2")]
+    pub name: ::prost::alloc::string::String,
+    /// Type of data in the summary.
+    #[prost(enumeration="mesh_plugin_data::ContentType", tag="3")]
+    pub content_type: i32,
+    /// JSON-serialized dictionary of ThreeJS classes configuration.
+    #[prost(string, tag="5")]
+    pub json_config: ::prost::alloc::string::String,
+    /// Shape of underlying data. Cache it here for performance reasons.
+    #[prost(int32, repeated, tag="6")]
+    pub shape: ::prost::alloc::vec::Vec<i32>,
+    /// Bitmask of the content types (as `1 << ContentType`) of all the
+    /// components belonging to the same mesh summary.
+    #[prost(uint32, tag="7")]
+    pub components: u32,
+}
+/// Nested message and enum types in `MeshPluginData`.
+pub mod mesh_plugin_data {
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+    #[repr(i32)]
+    pub enum ContentType {
+        Undefined = 0,
+        Vertex = 1,
+        Face = 2,
+        Color = 3,
+    }
+}
+/// Protocol buffer representing the value for an attr used to configure an Op.
+/// Comment indicates the corresponding attr type.  Only the field matching the
+/// attr type may be filled.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AttrValue {
+    #[prost(oneof="attr_value::Value", tags="2, 3, 4, 5, 6, 7, 8, 1, 9, 10")]
+    pub value: ::core::option::Option<attr_value::Value>,
+}
+/// Nested message and enum types in `AttrValue`.
+pub mod attr_value {
+    /// LINT.IfChange
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ListValue {
+        /// "list(string)"
+        #[prost(bytes="bytes", repeated, tag="2")]
+        pub s: ::prost::alloc::vec::Vec<::prost::bytes::Bytes>,
+        /// "list(int)"
+        #[prost(int64, repeated, tag="3")]
+        pub i: ::prost::alloc::vec::Vec<i64>,
+        /// "list(float)"
+        #[prost(float, repeated, tag="4")]
+        pub f: ::prost::alloc::vec::Vec<f32>,
+        /// "list(bool)"
+        #[prost(bool, repeated, tag="5")]
+        pub b: ::prost::alloc::vec::Vec<bool>,
+        /// "list(type)"
+        #[prost(enumeration="super::DataType", repeated, tag="6")]
+        pub r#type: ::prost::alloc::vec::Vec<i32>,
+        /// "list(shape)"
+        #[prost(message, repeated, tag="7")]
+        pub shape: ::prost::alloc::vec::Vec<super::TensorShapeProto>,
+        /// "list(tensor)"
+        #[prost(message, repeated, tag="8")]
+        pub tensor: ::prost::alloc::vec::Vec<super::TensorProto>,
+        /// "list(attr)"
+        #[prost(message, repeated, tag="9")]
+        pub func: ::prost::alloc::vec::Vec<super::NameAttrList>,
+    }
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Value {
+        /// "string"
+        #[prost(bytes, tag="2")]
+        S(::prost::bytes::Bytes),
+        /// "int"
+        #[prost(int64, tag="3")]
+        I(i64),
+        /// "float"
+        #[prost(float, tag="4")]
+        F(f32),
+        /// "bool"
+        #[prost(bool, tag="5")]
+        B(bool),
+        /// "type"
+        #[prost(enumeration="super::DataType", tag="6")]
+        Type(i32),
+        /// "shape"
+        #[prost(message, tag="7")]
+        Shape(super::TensorShapeProto),
+        /// "tensor"
+        #[prost(message, tag="8")]
+        Tensor(super::TensorProto),
+        /// any "list(...)"
+        #[prost(message, tag="1")]
+        List(ListValue),
+        /// "func" represents a function. func.name is a function's name or
+        /// a primitive op's name. func.attr.first is the name of an attr
+        /// defined for that function. func.attr.second is the value for
+        /// that attr in the instantiation.
+        #[prost(message, tag="10")]
+        Func(super::NameAttrList),
+        /// This is a placeholder only used in nodes defined inside a
+        /// function.  It indicates the attr value will be supplied when
+        /// the function is instantiated.
+        #[prost(string, tag="9")]
+        Placeholder(::prost::alloc::string::String),
+    }
+}
+/// A list of attr names and their values. The whole list is attached
+/// with a string name.  E.g., MatMul\[T=float\].
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NameAttrList {
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(map="string, message", tag="2")]
+    pub attr: ::std::collections::HashMap<::prost::alloc::string::String, AttrValue>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeDef {
+    /// The name given to this operator. Used for naming inputs,
+    /// logging, visualization, etc.  Unique within a single GraphDef.
+    /// Must match the regexp "\[A-Za-z0-9.\]\[A-Za-z0-9_>./\]*".
+    #[prost(string, tag="1")]
+    pub name: ::prost::alloc::string::String,
+    /// The operation name.  There may be custom parameters in attrs.
+    /// Op names starting with an underscore are reserved for internal use.
+    #[prost(string, tag="2")]
+    pub op: ::prost::alloc::string::String,
+    /// Each input is "node:src_output" with "node" being a string name and
+    /// "src_output" indicating which output tensor to use from "node". If
+    /// "src_output" is 0 the ":0" suffix can be omitted.  Regular inputs
+    /// may optionally be followed by control inputs that have the format
+    /// "^node".
+    #[prost(string, repeated, tag="3")]
+    pub input: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// A (possibly partial) specification for the device on which this
+    /// node should be placed.
+    #[prost(string, tag="4")]
+    pub device: ::prost::alloc::string::String,
+    /// Operation-specific graph-construction-time configuration.
+    #[prost(map="string, message", tag="5")]
+    pub attr: ::std::collections::HashMap<::prost::alloc::string::String, AttrValue>,
+}
+/// Version information for a piece of serialized data
+///
+/// There are different types of versions for each type of data
+/// (GraphDef, etc.), but they all have the same common shape
+/// described here.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VersionDef {
+    /// The version of the code that produced this data.
+    #[prost(int32, tag="1")]
+    pub producer: i32,
+    /// Any consumer below this version is not allowed to consume this data.
+    #[prost(int32, tag="2")]
+    pub min_consumer: i32,
+    /// Specific consumer versions which are disallowed (e.g. due to bugs).
+    #[prost(int32, repeated, tag="3")]
+    pub bad_consumers: ::prost::alloc::vec::Vec<i32>,
+}
+/// Represents the graph of operations
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GraphDef {
+    #[prost(message, repeated, tag="1")]
+    pub node: ::prost::alloc::vec::Vec<NodeDef>,
+    /// Compatibility versions of the graph.  See core/public/version.h for version
+    /// history.  The GraphDef version is distinct from the TensorFlow version, and
+    /// each release of TensorFlow will support a range of GraphDef versions.
+    #[prost(message, optional, tag="4")]
+    pub versions: ::core::option::Option<VersionDef>,
+    /// Deprecated single version field; use versions above instead.  Since all
+    /// GraphDef changes before "versions" was introduced were forward
+    /// compatible, this field is entirely ignored.
+    #[prost(int32, tag="3")]
+    pub version: i32,
+}
+/// Time/size stats recorded for a single execution of a graph node.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct NodeExecStats {
+    #[prost(string, tag="1")]
+    pub node_name: ::prost::alloc::string::String,
+    #[prost(int64, tag="2")]
+    pub all_start_micros: i64,
+    #[prost(int64, tag="3")]
+    pub op_start_rel_micros: i64,
+    #[prost(int64, tag="4")]
+    pub op_end_rel_micros: i64,
+    #[prost(int64, tag="5")]
+    pub all_end_rel_micros: i64,
+    #[prost(string, tag="8")]
+    pub timeline_label: ::prost::alloc::string::String,
+    #[prost(int64, tag="9")]
+    pub scheduled_micros: i64,
+    #[prost(uint32, tag="10")]
+    pub thread_id: u32,
+    #[prost(int64, tag="13")]
+    pub all_start_nanos: i64,
+    #[prost(int64, tag="14")]
+    pub op_start_rel_nanos: i64,
+    #[prost(int64, tag="15")]
+    pub op_end_rel_nanos: i64,
+    #[prost(int64, tag="16")]
+    pub all_end_rel_nanos: i64,
+    #[prost(int64, tag="17")]
+    pub scheduled_nanos: i64,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DeviceStepStats {
+    #[prost(string, tag="1")]
+    pub device: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag="2")]
+    pub node_stats: ::prost::alloc::vec::Vec<NodeExecStats>,
+    /// Its key is thread id.
+    #[prost(map="uint32, string", tag="3")]
+    pub thread_names: ::std::collections::HashMap<u32, ::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StepStats {
+    #[prost(message, repeated, tag="1")]
+    pub dev_stats: ::prost::alloc::vec::Vec<DeviceStepStats>,
+}
+/// Metadata output (i.e., non-Tensor) for a single Run() call.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RunMetadata {
+    /// Statistics traced for this step. Populated if tracing is turned on via the
+    /// "RunOptions" proto.
+    /// EXPERIMENTAL: The format and set of events may change in future versions.
+    #[prost(message, optional, tag="1")]
+    pub step_stats: ::core::option::Option<StepStats>,
+    /// Graphs of the partitions executed by executors.
+    #[prost(message, repeated, tag="3")]
+    pub partition_graphs: ::prost::alloc::vec::Vec<GraphDef>,
+}
@@ -86,6 +86,12 @@ impl TfRecord {
         }
     }
 
+    /// Checks whether the record's data matches its data CRC, like [`checksum`][Self::checksum]
+    /// but without the details of a mismatch.
+    pub fn checksum_valid(&self) -> bool {
+        self.data_crc.verify(&self.data)
+    }
+
     /// Creates a TFRecord from a data vector, computing the correct data CRC. Calling `checksum()`
     /// on this record will always succeed.
     pub fn from_data(data: Vec<u8>) -> Self {
@@ -179,6 +185,14 @@ impl<R: Read> TfRecordReader<R> {
         self.reader
     }
 
+    /// Gets a mutable reference to the underlying reader.
+    ///
+    /// Reading from it directly while a record is only partially read (i.e., after a `Truncated`
+    /// error) will corrupt that record.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+
     /// Attempts to read a TFRecord, pausing gracefully in the face of truncations. If the record
     /// is truncated, the result is a `Truncated` error; call `read_record` again once more data
     /// may have been written to resume reading where it left off. If the record is read
@@ -278,6 +292,92 @@ impl<R: Read> TfRecordReader<R> {
         self.header.clear(); // reset; caller may use this again
         Ok(TfRecord { data, data_crc })
     }
+
+    /// Reads the next TFRecord from a complete stream, like an event file that's no longer being
+    /// written, and verifies its data checksum. Returns `Ok(None)` at the end of the stream, if
+    /// no bytes of a new record remain.
+    ///
+    /// Unlike [`read_record`][Self::read_record], this treats truncation as an error rather than
+    /// a reason to wait for more data, so it's the simpler choice for reading finished files or
+    /// checking a writer's output.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`io::ErrorKind::UnexpectedEof`] if the stream ends partway through a record,
+    /// and with [`io::ErrorKind::InvalidData`] if either checksum doesn't match or the record is
+    /// too large to read. Underlying I/O errors are passed through.
+    pub fn next_record(&mut self) -> io::Result<Option<TfRecord>> {
+        let record = match self.read_record() {
+            Ok(record) => record,
+            Err(ReadRecordError::Truncated) if self.header.is_empty() => return Ok(None),
+            Err(ReadRecordError::Truncated) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    ReadRecordError::Truncated,
+                ))
+            }
+            Err(ReadRecordError::Io(e)) => return Err(e),
+            Err(e) => return Err(invalid_data(e)),
+        };
+        record.checksum().map_err(invalid_data)?;
+        Ok(Some(record))
+    }
+
+    /// Reads the next TFRecord like [`next_record`][Self::next_record], but treats a damaged end
+    /// of the stream as its end, as TensorBoard does for files whose writer crashed or is still
+    /// running. Returns `Ok(None)` if the final record is truncated, or if its length checksum
+    /// doesn't match and nothing but zero bytes follows its header (as in the unused tail of a
+    /// [preallocated][crate::TensorboardWriter::new_preallocated] file).
+    ///
+    /// A truncated record is kept in the reader's buffers, so calling this again once more data
+    /// has been written picks up where it left off.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`io::ErrorKind::InvalidData`] on corruption before the end of the stream: a
+    /// length checksum mismatch followed by more data, or a record whose data checksum doesn't
+    /// match. Underlying I/O errors are passed through.
+    pub fn read_record_lenient(&mut self) -> io::Result<Option<TfRecord>> {
+        let record = match self.read_record() {
+            Ok(record) => record,
+            Err(ReadRecordError::Truncated) => return Ok(None),
+            Err(ReadRecordError::BadLengthCrc(e)) => {
+                return if only_zeros_remain(&mut self.reader)? {
+                    Ok(None)
+                } else {
+                    Err(invalid_data(ReadRecordError::BadLengthCrc(e)))
+                };
+            }
+            Err(ReadRecordError::Io(e)) => return Err(e),
+            Err(e) => return Err(invalid_data(e)),
+        };
+        record.checksum().map_err(invalid_data)?;
+        Ok(Some(record))
+    }
+}
+
+/// Reads `reader` to the end, returning whether every byte was zero. Stops early at the first
+/// nonzero byte.
+fn only_zeros_remain<R: Read>(mut reader: R) -> io::Result<bool> {
+    let mut buf = [0u8; 4096];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => return Ok(true),
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if buf[..n].iter().any(|&b| b != 0) {
+            return Ok(false);
+        }
+    }
+}
+
+fn invalid_data<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
 }
 
 /// Fills `buf`'s remaining capacity from `reader`, or fails with `Truncated` if the reader is dry.
@@ -366,6 +466,87 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_next_record() {
+        let mut file = Vec::new();
+        TfRecord::from_data(b"one".to_vec())
+            .write(&mut file)
+            .unwrap();
+        TfRecord::from_data(b"two".to_vec())
+            .write(&mut file)
+            .unwrap();
+        let mut reader = TfRecordReader::new(Cursor::new(file.clone()));
+        assert_eq!(reader.next_record().unwrap().unwrap().data, b"one");
+        assert_eq!(reader.next_record().unwrap().unwrap().data, b"two");
+        assert!(reader.next_record().unwrap().is_none());
+        assert!(TfRecordReader::new(Cursor::new(Vec::new()))
+            .next_record()
+            .unwrap()
+            .is_none());
+
+        // Truncated in the header, and in the data.
+        for &len in &[24, 33] {
+            let mut reader = TfRecordReader::new(Cursor::new(file[..len].to_vec()));
+            reader.next_record().unwrap();
+            let e = reader.next_record().unwrap_err();
+            assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+        }
+
+        // Bad data checksum.
+        let mut bad = file.clone();
+        bad[12] ^= 0xff;
+        let e = TfRecordReader::new(Cursor::new(bad))
+            .next_record()
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_read_record_lenient() {
+        let mut file = Vec::new();
+        TfRecord::from_data(b"one".to_vec())
+            .write(&mut file)
+            .unwrap();
+        let mut second = Vec::new();
+        TfRecord::from_data(b"two".to_vec())
+            .write(&mut second)
+            .unwrap();
+
+        // A truncated final record reads as the end, and resumes once complete.
+        let mut contents = file.clone();
+        contents.extend(&second[..15]);
+        let mut reader =
+            TfRecordReader::new(ScriptedReader::new(vec![contents, second[15..].to_vec()]));
+        assert_eq!(reader.read_record_lenient().unwrap().unwrap().data, b"one");
+        assert!(reader.read_record_lenient().unwrap().is_none());
+        assert_eq!(reader.read_record_lenient().unwrap().unwrap().data, b"two");
+        assert!(reader.read_record_lenient().unwrap().is_none());
+
+        // A zero-filled tail reads as the end.
+        let mut contents = file.clone();
+        contents.extend(vec![0; 100]);
+        let mut reader = TfRecordReader::new(Cursor::new(contents));
+        reader.read_record_lenient().unwrap().unwrap();
+        assert!(reader.read_record_lenient().unwrap().is_none());
+
+        // A bad length checksum before more records is corruption.
+        let mut contents = file.clone();
+        contents[8] ^= 0xff;
+        contents.extend(&second);
+        let e = TfRecordReader::new(Cursor::new(contents))
+            .read_record_lenient()
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+
+        // So is a bad data checksum, even at the end.
+        let mut contents = file.clone();
+        contents[12] ^= 0xff;
+        let e = TfRecordReader::new(Cursor::new(contents))
+            .read_record_lenient()
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+
     #[test]
     fn test_length_crc_mismatch() {
         let mut file = Vec::new();
@@ -430,6 +611,14 @@ mod tests {
         assert_eq!(e.to_string(), expected_message);
     }
 
+    #[test]
+    fn test_checksum_valid() {
+        let mut record = TfRecord::from_data(b"hello world".to_vec());
+        assert!(record.checksum_valid());
+        record.data[0] = b'j';
+        assert!(!record.checksum_valid());
+    }
+
     #[test]
     fn test_from_data() {
         let test_cases = vec![
@@ -1,14 +1,18 @@
 use std::ffi::OsString;
 use std::fs::File;
-use std::io::{self, BufWriter, Write};
-use std::path::Path;
+use std::io::{self, BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::time::SystemTime;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
 
 use prost::Message;
 
+use crate::hparams::{self, HParamValue};
 use crate::proto::tensorboard as pb;
+use crate::summary::markdown_table;
 use crate::tf_record::TfRecord;
+use crate::{AsyncSummaryWrite, SummaryWrite};
 
 /// Utility for writing TensorBoard event files.
 ///
@@ -16,15 +20,219 @@ use crate::tf_record::TfRecord;
 /// TFRecord should be a serialized [`tensorboard.Event`][pb::Event] protocol buffer. Most events
 /// contain summary values; you can use the [`SummaryBuilder`][crate::SummaryBuilder] utility to
 /// build those.
+///
+/// # Flushing
+///
+/// Records are buffered until the writer is [flushed][Self::flush] (or [set to flush
+/// automatically][Self::with_autoflush]). A writer also flushes when it's dropped, but errors are
+/// lost then. To check that everything was written, end with [`finish`][Self::finish] or
+/// [`close`][Self::close] instead.
+///
+/// # Deterministic output
+///
+/// The bytes that a writer produces depend only on the events written to it, except that events
+/// like the [file version][Self::write_file_version] are stamped with the current time, and
+/// [`Writer::new`] picks a unique file name. For byte-identical event files (e.g., for golden-file
+/// tests), fix both:
+///
+/// - use [`with_clock`][Self::with_clock] to read the time from a fixed clock, and
+/// - use [`new_with_file_name`][Self::new_with_file_name] to choose the file name, or
+///   [`wrap`][Self::wrap] a buffer.
+///
+/// Then pass fixed wall times to methods like [`write_summary`][Self::write_summary].
+///
+/// ```
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+///
+/// use tensorboard_writer::{SummaryBuilder, TensorboardWriter};
+///
+/// fn write_run() -> Vec<u8> {
+///     let epoch = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+///     let mut writer = TensorboardWriter::wrap(Vec::new()).with_clock(move || epoch);
+///     writer.write_file_version().unwrap();
+///     let summ = SummaryBuilder::new().scalar("loss", 0.5).build();
+///     writer.write_summary(epoch, 0, summ).unwrap();
+///     writer.into_vec()
+/// }
+/// assert_eq!(write_run(), write_run());
+/// ```
 pub struct Writer<W> {
-    writer: W,
+    /// Always `Some` until the writer is unwrapped.
+    writer: Option<W>,
+    /// Flushes the writer if it's dirty, for `Drop`. This is captured when a record is first
+    /// written, since `Drop` can't require `W: Write`; a writer that has never written a record
+    /// has nothing to flush.
+    drop_flush: Option<DropFlush<W>>,
+    sync: Option<SyncState<W>>,
+    debug_dump: Option<Box<dyn Write + Send>>,
+    /// Whether any records have been written since the last flush.
+    dirty: bool,
+    /// If set, wall times are stored relative to this time rather than the Unix epoch.
+    time_base: Option<SystemTime>,
+    /// Source of the current time for events that are stamped automatically.
+    clock: Box<dyn FnMut() -> SystemTime + Send>,
+    /// Records are padded to start at multiples of this many bytes.
+    alignment: usize,
+    /// Bytes of records (and padding) written so far, for alignment.
+    bytes_written: u64,
+    /// Whether `write_summary` should drop summaries with no values.
+    skip_empty_summaries: bool,
+    /// If set, events for which this returns false are dropped.
+    filter: Option<Box<EventFilter>>,
+    /// Whether a file version event has been written.
+    wrote_file_version: bool,
+    /// Whether writing a second file version event is an error.
+    strict_file_version: bool,
+    /// The encoded wall time of the last event written, if any.
+    last_wall_time: Option<f64>,
+    /// When to flush automatically after writing a record.
+    flush_policy: FlushPolicy,
+    /// Records written since the last flush, for the flush policy.
+    unflushed_records: usize,
+    /// When the writer was last flushed (or created), for the flush policy.
+    last_flush: Instant,
+    /// The path of the event file, if this writer opened it.
+    path: Option<PathBuf>,
+    /// If set, the writer moves to a new event file once the current one is big enough.
+    rotation: Option<Rotation<W>>,
+    /// The value of `bytes_written` when the current event file was started.
+    file_start: u64,
+    /// The total size of the file version headers written when rotating, which
+    /// `write_summary_counted` doesn't count toward the summary being written.
+    rotation_header_bytes: u64,
+    /// The step for the next call to `write_summary_auto`.
+    step: i64,
+}
+
+/// The type of `Writer::drop_flush`.
+type DropFlush<W> = fn(&mut Writer<W>) -> io::Result<()>;
+
+/// Settings for [`Writer::new_rotating`]. The function that opens new files is captured when the
+/// writer is created, since that's the only place where we know what `W` is.
+struct Rotation<W> {
+    run_directory: PathBuf,
+    max_bytes: u64,
+    open: fn(&Path) -> io::Result<(W, PathBuf)>,
+}
+
+/// Error returned by the [`Writer`] methods that stamp events with a wall time.
+///
+/// This converts to and from [`io::Error`], so `?` works in functions that return either.
+#[derive(Debug, thiserror::Error)]
+pub enum WriteError {
+    /// Writing to the underlying output stream failed.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// A wall time is before the Unix epoch, so it can't be stored as seconds since the epoch.
+    /// Use a [time base][Writer::with_time_base] to store such times relative to a later one.
+    #[error("wall time {0:?} is before the Unix epoch")]
+    TimeBeforeEpoch(SystemTime),
+}
+
+impl From<WriteError> for io::Error {
+    fn from(e: WriteError) -> Self {
+        match e {
+            WriteError::Io(e) => e,
+            e @ WriteError::TimeBeforeEpoch(_) => io::Error::new(io::ErrorKind::InvalidInput, e),
+        }
+    }
+}
+
+/// The status of a [`Writer::write_session_log`] event.
+pub use pb::session_log::SessionStatus as SessionLogStatus;
+
+/// A predicate that decides whether a [`Writer`] should write an event.
+type EventFilter = dyn FnMut(&pb::Event) -> bool + Send;
+
+/// How often a [`Writer`] should sync its output to durable storage, as with
+/// [`File::sync_all`].
+///
+/// Syncs only ever happen as part of a call to [`Writer::flush`]; a policy just decides which of
+/// those flushes also sync. Syncing less often trades a bounded window of durability (records
+/// that are flushed to the OS but may be lost in a power failure) for throughput.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SyncPolicy {
+    /// Never sync automatically. This is the default.
+    Never,
+    /// Sync on every `n`th flush. `EveryN(1)` syncs on every flush, and `EveryN(0)` never syncs.
+    EveryN(usize),
+    /// Sync on a flush if at least this much time has passed since the last sync (or since the
+    /// policy was set).
+    Interval(Duration),
+}
+
+/// When a [`Writer`] should flush its output automatically, so that readers like TensorBoard see
+/// new records without explicit calls to [`Writer::flush`].
+///
+/// Automatic flushes happen right after a record is written, and count as flushes for the
+/// [`SyncPolicy`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// Never flush automatically. This is the default.
+    Never,
+    /// Flush after every record.
+    EveryRecord,
+    /// Flush after every `n` records. `EveryN(1)` is the same as `EveryRecord`, and `EveryN(0)`
+    /// never flushes.
+    EveryN(usize),
+    /// Flush after a record if at least this much time has passed since the last flush (or since
+    /// the writer was created).
+    Interval(Duration),
+}
+
+/// An output stream that can be synced to durable storage.
+pub trait SyncAll {
+    /// Flushes any buffered data and waits until it has reached durable storage.
+    fn sync_all(&mut self) -> io::Result<()>;
+}
+
+impl SyncAll for File {
+    fn sync_all(&mut self) -> io::Result<()> {
+        File::sync_all(self)
+    }
+}
+
+impl<W: Write + SyncAll> SyncAll for BufWriter<W> {
+    fn sync_all(&mut self) -> io::Result<()> {
+        self.flush()?;
+        self.get_mut().sync_all()
+    }
+}
+
+/// Bookkeeping for a [`SyncPolicy`]. The sync function is captured when the policy is set, since
+/// that's the only place where we know that `W: SyncAll`.
+struct SyncState<W> {
+    policy: SyncPolicy,
+    sync: fn(&mut W) -> io::Result<()>,
+    flushes: usize,
+    last_sync: Instant,
+}
+
+impl<W> SyncState<W> {
+    /// Records a flush, syncing `writer` if the policy calls for it.
+    fn after_flush(&mut self, writer: &mut W) -> io::Result<()> {
+        self.flushes += 1;
+        let due = match self.policy {
+            SyncPolicy::Never => false,
+            SyncPolicy::EveryN(n) => n > 0 && self.flushes % n == 0,
+            SyncPolicy::Interval(interval) => self.last_sync.elapsed() >= interval,
+        };
+        if due {
+            (self.sync)(writer)?;
+            self.last_sync = Instant::now();
+        }
+        Ok(())
+    }
 }
 
 static GLOBAL_UID: AtomicU64 = AtomicU64::new(0);
 
 /// Creates a unique name for an event file, incorporating sources of entropy including the
-/// timestamp, hostname, process ID, and a per-process global counter.
-fn event_file_name() -> OsString {
+/// timestamp, hostname, process ID, and a per-process global counter. If a distributed training
+/// rank is given, it's included after the hostname. The timestamp and counter are zero-padded, so
+/// that files created later by the same process sort after earlier ones, as TensorBoard reads
+/// them.
+fn event_file_name(rank: Option<usize>) -> OsString {
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map_or(0, |dt| dt.as_secs());
@@ -34,93 +242,977 @@ fn event_file_name() -> OsString {
 
     let mut result = OsString::from(format!("events.out.tfevents.{now:010}."));
     result.push(hostname);
+    if let Some(rank) = rank {
+        result.push(format!(".rank{rank}"));
+    }
+    result.push(format!(".{pid}.{uid:010}"));
+    result
+}
+
+/// Creates a unique name for a file that's not yet ready to be read. It doesn't contain
+/// `tfevents`, so TensorBoard ignores it.
+fn temp_file_name() -> OsString {
+    let hostname = hostname::get().unwrap_or_default();
+    let pid = std::process::id();
+    let uid = GLOBAL_UID.fetch_add(1, Ordering::Relaxed);
+    let mut result = OsString::from(".tmp.");
+    result.push(hostname);
     result.push(format!(".{pid}.{uid}"));
     result
 }
 
+/// Creates a run name like `20230101-143000.hostname` from the given time (in UTC) and the
+/// hostname.
+fn auto_run_name(time: SystemTime) -> OsString {
+    let secs = time
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |dt| dt.as_secs());
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let secs_of_day = secs % 86400;
+    let mut result = OsString::from(format!(
+        "{year:04}{month:02}{day:02}-{:02}{:02}{:02}.",
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+    ));
+    result.push(hostname::get().unwrap_or_default());
+    result
+}
+
+/// Converts a count of days since 1970-01-01 to a proleptic Gregorian `(year, month, day)`.
+///
+/// This is Howard Hinnant's `civil_from_days` algorithm:
+/// <https://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097); // [0, 146096]
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365; // [0, 399]
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100); // [0, 365]
+    let mp = (5 * doy + 2) / 153; // [0, 11], starting from March
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Creates a run directory and its ancestors, if they don't already exist.
+///
+/// The run directory may be a symlink to a directory elsewhere (e.g., on scratch storage), in
+/// which case event files are written into the link target. If the link is dangling, its target
+/// is created, since `create_dir_all` would otherwise fail with "file exists".
+fn create_run_directory(run_directory: &Path) -> io::Result<()> {
+    let target = match std::fs::symlink_metadata(run_directory) {
+        Ok(meta) if meta.file_type().is_symlink() && !run_directory.exists() => {
+            // Relative link targets are relative to the directory containing the link.
+            let parent = run_directory.parent().unwrap_or_else(|| Path::new(""));
+            parent.join(std::fs::read_link(run_directory)?)
+        }
+        _ => run_directory.to_path_buf(),
+    };
+    std::fs::create_dir_all(target)
+}
+
+/// Creates a new event file with the given name in an existing run directory.
+fn create_event_file(
+    run_directory: &Path,
+    file_name: OsString,
+) -> io::Result<(BufWriter<File>, PathBuf)> {
+    let path = run_directory.join(file_name);
+    let file = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create_new(true)
+        .open(&path)?;
+    Ok((BufWriter::new(file), path))
+}
+
 impl Writer<BufWriter<File>> {
     /// Creates a new TensorBoard event file in the given run directory.
     ///
-    /// The run directory and its ancestors will be created if they do not exist.
+    /// The run directory and its ancestors will be created if they do not exist. The run
+    /// directory may also be a symlink to a directory, which is followed (and whose target is
+    /// created if needed).
     ///
     /// # Errors
     ///
     /// Errors if the run directory cannot be created, or in the unlikely event that the newly
     /// chosen name for the event file is already taken.
     pub fn new<P: AsRef<Path>>(run_directory: P) -> io::Result<Self> {
+        Self::create_in(run_directory.as_ref(), event_file_name(None))
+    }
+
+    /// Creates a new TensorBoard event file like [`Writer::new`] and writes its [file
+    /// version][Self::write_file_version] header, so that it's ready for summaries.
+    ///
+    /// The header is written and flushed under a temporary name that TensorBoard ignores, and
+    /// then the file is renamed into place, so a reader polling the run directory never sees an
+    /// empty event file.
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as [`Writer::new`], or if the header can't be written or
+    /// the file can't be renamed. On error, the temporary file is removed.
+    pub fn new_initialized<P: AsRef<Path>>(run_directory: P) -> io::Result<Self> {
+        let run_directory = run_directory.as_ref();
+        create_run_directory(run_directory)?;
+        let (file, temp_path) = create_event_file(run_directory, temp_file_name())?;
+        let mut writer = Self::wrap(file);
+        let path = run_directory.join(event_file_name(None));
+        let result = writer
+            .write_file_version()
+            .map_err(io::Error::from)
+            .and_then(|()| writer.flush())
+            .and_then(|()| std::fs::rename(&temp_path, &path));
+        if let Err(e) = result {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(e);
+        }
+        writer.path = Some(path);
+        Ok(writer)
+    }
+
+    /// Creates a new TensorBoard event file with the given name in the given run directory.
+    ///
+    /// This is mostly useful for [deterministic output](Self#deterministic-output); usually, it's
+    /// better to let [`Writer::new`] choose a unique name. The name should contain `tfevents` for
+    /// TensorBoard to read the file.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the run directory cannot be created, or if a file with this name already exists.
+    pub fn new_with_file_name<P: AsRef<Path>>(
+        run_directory: P,
+        file_name: &str,
+    ) -> io::Result<Self> {
+        Self::create_in(run_directory.as_ref(), OsString::from(file_name))
+    }
+
+    /// Creates a new TensorBoard event file in the given run directory, for one process of a
+    /// distributed training job.
+    ///
+    /// Each process should write its own event file in the shared run directory, and TensorBoard
+    /// will merge them. The `rank` is included in the file name, so that the files are easy to
+    /// tell apart and can't collide even if the processes share a hostname and PID (e.g., in
+    /// separate containers) and start in the same second.
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as [`Writer::new`].
+    pub fn new_for_rank<P: AsRef<Path>>(run_directory: P, rank: usize) -> io::Result<Self> {
+        Self::create_in(run_directory.as_ref(), event_file_name(Some(rank)))
+    }
+
+    fn create_in(run_directory: &Path, file_name: OsString) -> io::Result<Self> {
+        create_run_directory(run_directory)?;
+        let (file, path) = create_event_file(run_directory, file_name)?;
+        let mut writer = Self::wrap(file);
+        writer.path = Some(path);
+        Ok(writer)
+    }
+
+    /// Creates a new TensorBoard event file like [`Writer::new_initialized`], and rolls over to
+    /// a fresh event file in the same run directory whenever the current one reaches `max_bytes`.
+    ///
+    /// Each new file starts with its own file version header. The check happens before each
+    /// record is written, so a file may exceed `max_bytes` by up to one record, and records are
+    /// never split across files. TensorBoard reads all the event files in a run directory as one
+    /// run, so rotation is invisible in the dashboards, but it keeps individual files small
+    /// enough to load, copy, and clean up easily. [`path`][Writer::path] gives the current file.
+    ///
+    /// # Errors
+    ///
+    /// Errors with [`io::ErrorKind::InvalidInput`] if `max_bytes` is 0, or under the same
+    /// conditions as [`Writer::new_initialized`]. Writes error if a new file can't be created.
+    pub fn new_rotating<P: AsRef<Path>>(run_directory: P, max_bytes: u64) -> io::Result<Self> {
+        if max_bytes == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "max_bytes for a rotating writer must be positive",
+            ));
+        }
         let run_directory = run_directory.as_ref();
-        std::fs::create_dir_all(run_directory)?;
-        let filename = run_directory.join(event_file_name());
-        let file = std::fs::OpenOptions::new()
+        let mut writer = Self::new_initialized(run_directory)?;
+        writer.rotation = Some(Rotation {
+            run_directory: run_directory.to_path_buf(),
+            max_bytes,
+            open: |run_directory| create_event_file(run_directory, event_file_name(None)),
+        });
+        Ok(writer)
+    }
+
+    /// Creates a new TensorBoard event file like [`Writer::new`], but pre-sizes it to
+    /// `expected_bytes` to reduce fragmentation and file extension overhead for very large runs.
+    ///
+    /// The pre-sized region is zero-filled, so the file must be [closed][Self::close] to truncate
+    /// it to the size of the data actually written. Until then, readers will see the zeros after
+    /// the last record as a corrupt record, so TensorBoard may warn about data loss while the run
+    /// is in progress.
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as [`Writer::new`], or if the file can't be resized.
+    pub fn new_preallocated<P: AsRef<Path>>(
+        run_directory: P,
+        expected_bytes: u64,
+    ) -> io::Result<Self> {
+        let writer = Self::new(run_directory)?;
+        writer.get_ref().get_ref().set_len(expected_bytes)?;
+        Ok(writer)
+    }
+
+    /// Opens an existing event file to append records to it, e.g., to resume logging to the same
+    /// file after a training job restarts.
+    ///
+    /// No file version header is written, since the file should already start with one. If the
+    /// previous writer crashed partway through a record, run [`repair_event_file`] first, or the
+    /// partial record will hide the new ones from readers.
+    ///
+    /// [`repair_event_file`]: crate::repair_event_file
+    ///
+    /// # Errors
+    ///
+    /// Errors if the file doesn't exist or can't be opened for writing.
+    pub fn open_append<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut file = std::fs::OpenOptions::new()
             .read(true)
-            .write(true)
-            .create_new(true)
-            .open(filename)?;
-        Ok(Self::wrap(BufWriter::new(file)))
+            .append(true)
+            .open(path)?;
+        // Appends always go to the end, but `close` truncates at the stream position, so it
+        // needs to start there too.
+        let len = file.seek(SeekFrom::End(0))?;
+        let mut writer = Self::wrap(BufWriter::new(file));
+        writer.bytes_written = len;
+        writer.path = Some(path.to_path_buf());
+        Ok(writer)
+    }
+
+    /// Flushes this writer and closes its event file, truncating the file to the end of the last
+    /// record written. This trims any space reserved by [`new_preallocated`][Self::new_preallocated].
+    ///
+    /// Dropping a writer also closes its file, but ignores errors and doesn't truncate it.
+    pub fn close(self) -> io::Result<()> {
+        self.close_file().map(drop)
+    }
+
+    /// Closes this writer like [`close`][Self::close], and also sets the event file's modification
+    /// time to the wall time of the last event written. This keeps file metadata consistent with
+    /// the data for tools that sort files by modification time, e.g., when backfilling old runs.
+    ///
+    /// If no events have been written, the modification time is left alone.
+    pub fn close_with_mtime(self) -> io::Result<()> {
+        let mtime = match self.last_wall_time {
+            None => None,
+            Some(secs) => Some(self.decode_time(secs)?),
+        };
+        let file = self.close_file()?;
+        match mtime {
+            Some(mtime) => file.set_modified(mtime),
+            None => Ok(()),
+        }
+    }
+
+    /// Flushes this writer and truncates its file to the end of the last record, returning the
+    /// file.
+    fn close_file(mut self) -> io::Result<File> {
+        self.flush()?;
+        let mut file = self.into_inner().into_inner().map_err(|e| e.into_error())?;
+        let len = file.stream_position()?;
+        file.set_len(len)?;
+        Ok(file)
+    }
+
+    /// Creates a new run directory under `base_directory` and a new event file in it.
+    ///
+    /// The run is named after the current time (in UTC) and hostname, like
+    /// `20230101-143000.hostname`, so runs sort chronologically. Two writers created in the same
+    /// second on the same host share a run directory, but still get distinct event files.
+    ///
+    /// # Errors
+    ///
+    /// Errors under the same conditions as [`Writer::new`].
+    pub fn new_auto_run<P: AsRef<Path>>(base_directory: P) -> io::Result<Self> {
+        let run_directory = base_directory
+            .as_ref()
+            .join(auto_run_name(SystemTime::now()));
+        Self::new(run_directory)
     }
 }
 
+const UNWRAPPED: &str = "writer used after being unwrapped";
+
 impl<W> Writer<W> {
     /// Wraps an existing writer object. Usually you will want to use [`Writer::new`]; this method
     /// is appropriate if not writing to a file.
     pub fn wrap(writer: W) -> Self {
-        Self { writer }
+        Self {
+            writer: Some(writer),
+            drop_flush: None,
+            sync: None,
+            debug_dump: None,
+            dirty: false,
+            time_base: None,
+            clock: Box::new(SystemTime::now),
+            alignment: 1,
+            bytes_written: 0,
+            skip_empty_summaries: false,
+            filter: None,
+            wrote_file_version: false,
+            strict_file_version: false,
+            last_wall_time: None,
+            flush_policy: FlushPolicy::Never,
+            unflushed_records: 0,
+            last_flush: Instant::now(),
+            path: None,
+            rotation: None,
+            file_start: 0,
+            rotation_header_bytes: 0,
+            step: 0,
+        }
     }
 
     /// Gets a reference to the underlying writer.
     pub fn get_ref(&self) -> &W {
-        &self.writer
+        self.writer.as_ref().expect(UNWRAPPED)
     }
 
     /// Gets a mutable reference to the underlying writer.
     pub fn get_mut(&mut self) -> &mut W {
-        &mut self.writer
+        self.writer.as_mut().expect(UNWRAPPED)
     }
 
-    /// Unwraps this TensorBoard writer, returning the underlying writer.
-    pub fn into_inner(self) -> W {
-        self.writer
+    /// The path of the event file that this writer opened, like
+    /// `logs/run1/events.out.tfevents.1600000000.hostname.1234.0000000000`, or `None` if it
+    /// [wraps][Writer::wrap] another writer.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Whether a [file version][Writer::write_file_version] event has been written by this writer.
+    pub fn has_file_version(&self) -> bool {
+        self.wrote_file_version
+    }
+
+    /// Unwraps this TensorBoard writer, returning the underlying writer. Unlike dropping, this
+    /// doesn't flush it.
+    pub fn into_inner(mut self) -> W {
+        self.writer.take().expect(UNWRAPPED)
     }
 }
 
-fn time_f64(time: SystemTime) -> std::io::Result<f64> {
+impl<W> Drop for Writer<W> {
+    fn drop(&mut self) {
+        if let (Some(drop_flush), Some(_)) = (self.drop_flush, &self.writer) {
+            let _ = drop_flush(self);
+        }
+    }
+}
+
+impl Writer<Vec<u8>> {
+    /// Unwraps this in-memory TensorBoard writer, returning the bytes written so far.
+    ///
+    /// To write into a buffer that you want to keep ownership of, wrap a mutable reference
+    /// instead: `Writer::wrap(&mut buf)`.
+    pub fn into_vec(self) -> Vec<u8> {
+        self.into_inner()
+    }
+}
+
+/// The largest alignment supported by [`Writer::with_alignment`].
+pub const MAX_ALIGNMENT: usize = 4096;
+
+static ZEROS: [u8; MAX_ALIGNMENT] = [0; MAX_ALIGNMENT];
+
+/// Size of a TFRecord's framing: a 12-byte header and a 4-byte footer.
+const RECORD_OVERHEAD: usize = 16;
+
+/// How many bytes of padding are needed after `offset` bytes to reach a multiple of `alignment`.
+pub(crate) fn padding_len(offset: u64, alignment: usize) -> usize {
+    let alignment = alignment.max(1) as u64;
+    ((alignment - offset % alignment) % alignment) as usize
+}
+
+/// The tag under which [`Writer::write_file_version_with_cmdline`] records the command line.
+pub const CMDLINE_TAG: &str = "_cmdline";
+
+/// The tag under which [`Writer::write_build_info`] records build information.
+pub const BUILD_INFO_TAG: &str = "_build_info";
+
+/// The tag under which [`Writer::write_layout`] records a Custom Scalars layout. This is the tag
+/// that the dashboard looks for.
+pub const CUSTOM_SCALARS_TAG: &str = "custom_scalars__config__";
+
+/// Information about the build of the program that is writing a run, for traceability.
+///
+/// Populate this at compile time, e.g., with `env!("CARGO_PKG_VERSION")` or a build script like
+/// `vergen`, and log it with [`Writer::write_build_info`]. Empty fields are omitted.
+///
+/// ```
+/// use tensorboard_writer::BuildInfo;
+///
+/// let info = BuildInfo {
+///     version: env!("CARGO_PKG_VERSION").to_string(),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// The Git commit that the program was built from.
+    pub git_sha: String,
+    /// When the program was built, in any format (ideally RFC 3339).
+    pub build_time: String,
+    /// The version of the program.
+    pub version: String,
+}
+
+/// Quotes a command-line argument for display, POSIX shell style, if it isn't just plain words.
+fn quote_arg(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && !arg
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '\'' | '"' | '\\'));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+fn time_f64(time: SystemTime) -> Result<f64, WriteError> {
     Ok(time
         .duration_since(std::time::UNIX_EPOCH)
-        .map_err(io::Error::other)?
+        .map_err(|_| WriteError::TimeBeforeEpoch(time))?
         .as_secs_f64())
 }
 
+impl<W: Write + SyncAll> Writer<W> {
+    /// Sets how often calls to [`flush`][Self::flush] should also sync the output to durable
+    /// storage. This replaces any previous sync policy and resets its counters.
+    pub fn with_sync_policy(mut self, policy: SyncPolicy) -> Self {
+        self.sync = Some(SyncState {
+            policy,
+            sync: W::sync_all,
+            flushes: 0,
+            last_sync: Instant::now(),
+        });
+        self
+    }
+}
+
 impl<W: Write> Writer<W> {
-    /// [Flushes][std::io::Write::flush] the underlying writer.
+    /// Also writes every event to `dump` in protobuf text format, for debugging.
+    ///
+    /// The text is meant for humans (and `grep`), like the `.pbtxt` files written by TensorFlow
+    /// tools, and is not guaranteed to be parseable. Events are separated by blank lines. The dump
+    /// is flushed whenever this writer is. If the dump is a file in the run directory, make sure
+    /// that its name does *not* contain `tfevents`, or TensorBoard will try to read it as an event
+    /// file.
+    pub fn with_debug_dump<D: Write + Send + 'static>(mut self, dump: D) -> Self {
+        self.debug_dump = Some(Box::new(dump));
+        self
+    }
+
+    /// Pads the output with zero bytes after each record so that every record starts at a multiple
+    /// of `alignment` bytes from the start of the stream, e.g., for readers that `mmap` the file
+    /// and want aligned reads.
+    ///
+    /// **TensorBoard can't read padded files.** Read them with an
+    /// [`EventReader`][crate::EventReader] configured with the same
+    /// [alignment][crate::EventReader::with_alignment]. An alignment of 0 or 1 means no padding.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alignment` is greater than [`MAX_ALIGNMENT`].
+    pub fn with_alignment(mut self, alignment: usize) -> Self {
+        assert!(
+            alignment <= MAX_ALIGNMENT,
+            "alignment {} exceeds maximum of {}",
+            alignment,
+            MAX_ALIGNMENT
+        );
+        self.alignment = alignment.max(1);
+        self
+    }
+
+    /// Makes [`write_summary`][Self::write_summary] skip summaries that have no values, instead of
+    /// writing empty events. This is handy when conditional logging code may not add anything to
+    /// a [`SummaryBuilder`][crate::SummaryBuilder]. Use
+    /// [`write_summary_counted`][Self::write_summary_counted] to tell whether a summary was
+    /// written: it returns 0 for skipped summaries.
+    pub fn with_skip_empty_summaries(mut self) -> Self {
+        self.skip_empty_summaries = true;
+        self
+    }
+
+    /// Sets when this writer flushes automatically. By default, it only flushes when
+    /// [`flush`][Self::flush] is called (or when it's dropped), so records may sit in a buffer
+    /// for a while before TensorBoard can see them.
+    pub fn with_autoflush(mut self, policy: FlushPolicy) -> Self {
+        self.flush_policy = policy;
+        self
+    }
+
+    /// Makes a second call to [`write_file_version`][Self::write_file_version] (or the methods
+    /// built on it) fail with [`AlreadyExists`][io::ErrorKind::AlreadyExists] instead of writing
+    /// a duplicate header. This catches helper functions that each think that they're responsible
+    /// for the header.
+    pub fn with_strict_file_version(mut self) -> Self {
+        self.strict_file_version = true;
+        self
+    }
+
+    /// Consults `predicate` before writing each event, and drops the events for which it returns
+    /// false. This allows arbitrary sampling policies, like logging densely early in training and
+    /// sparsely later, or dropping repeated values.
+    ///
+    /// The predicate sees every event written through [`write_event`][Self::write_event] and the
+    /// methods built on it, including the [file version][Self::write_file_version], but not raw
+    /// [records][Self::write_record]. It replaces any previous filter.
+    ///
+    /// ```
+    /// use tensorboard_writer::TensorboardWriter;
+    ///
+    /// // Log every step up to 1000, then every 100th step.
+    /// let writer = TensorboardWriter::wrap(Vec::new())
+    ///     .with_filter(|event| event.step < 1000 || event.step % 100 == 0);
+    /// ```
+    pub fn with_filter<F: FnMut(&pb::Event) -> bool + Send + 'static>(
+        mut self,
+        predicate: F,
+    ) -> Self {
+        self.filter = Some(Box::new(predicate));
+        self
+    }
+
+    /// Reads the current time from `clock` instead of the system clock. This is used for events
+    /// that are stamped with the time automatically, like the [file
+    /// version][Self::write_file_version]; see [deterministic output](Self#deterministic-output).
+    pub fn with_clock<C: FnMut() -> SystemTime + Send + 'static>(mut self, clock: C) -> Self {
+        self.clock = Box::new(clock);
+        self
+    }
+
+    /// Stores wall times as seconds since `base` instead of since the Unix epoch.
+    ///
+    /// Small magnitudes keep more of an `f64`'s precision, which helps when profiling
+    /// sub-millisecond steps, and TensorBoard's "relative" time axis still shows the right
+    /// durations. The tradeoff is that absolute times are lost: TensorBoard will think that the run
+    /// happened in January 1970. Times before `base` are stored as negative offsets.
+    pub fn with_time_base(mut self, base: SystemTime) -> Self {
+        self.time_base = Some(base);
+        self
+    }
+
+    /// Converts a wall time to seconds since the time base (usually the Unix epoch).
+    fn encode_time(&self, time: SystemTime) -> Result<f64, WriteError> {
+        match self.time_base {
+            None => time_f64(time),
+            Some(base) => Ok(match time.duration_since(base) {
+                Ok(elapsed) => elapsed.as_secs_f64(),
+                Err(e) => -e.duration().as_secs_f64(),
+            }),
+        }
+    }
+
+    /// Converts seconds since the time base back to a wall time; the inverse of `encode_time`.
+    fn decode_time(&self, secs: f64) -> io::Result<SystemTime> {
+        let base = self.time_base.unwrap_or(std::time::UNIX_EPOCH);
+        let offset = Duration::try_from_secs_f64(secs.abs())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let time = if secs < 0.0 {
+            base.checked_sub(offset)
+        } else {
+            base.checked_add(offset)
+        };
+        time.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "wall time out of range"))
+    }
+
+    /// [Flushes][std::io::Write::flush] the underlying writer. If a [sync
+    /// policy][Self::with_sync_policy] is set, this may also sync it to durable storage.
     pub fn flush(&mut self) -> io::Result<()> {
-        self.writer.flush()
+        self.get_mut().flush()?;
+        self.dirty = false;
+        self.unflushed_records = 0;
+        self.last_flush = Instant::now();
+        if let Some(dump) = &mut self.debug_dump {
+            dump.flush()?;
+        }
+        if let Some(sync) = &mut self.sync {
+            sync.after_flush(self.writer.as_mut().expect(UNWRAPPED))?;
+        }
+        Ok(())
+    }
+
+    /// Flushes this writer and returns the underlying writer, reporting any error from the final
+    /// flush rather than losing it on drop.
+    pub fn finish(mut self) -> io::Result<W> {
+        self.flush()?;
+        Ok(self.into_inner())
+    }
+
+    /// Like [`flush`][Self::flush], but does nothing if no records have been written since the
+    /// last flush. This avoids redundant flushes (and syncs) on steps where nothing was logged.
+    pub fn flush_if_dirty(&mut self) -> io::Result<()> {
+        if self.dirty {
+            self.flush()?;
+        }
+        Ok(())
     }
 
     /// Writes a raw TFRecord to the output stream. You may find it more convenient to use
     /// [`write_event`][Self::write_event] instead, which computes the record checksum for you.
     pub fn write_record(&mut self, record: &TfRecord) -> io::Result<()> {
-        record.write(&mut self.writer)
+        if let Some(rotation) = &self.rotation {
+            // An empty file is never rotated away, so the header that `rotate` writes always
+            // lands in the new file.
+            let file_len = self.bytes_written - self.file_start;
+            if file_len > 0 && file_len >= rotation.max_bytes {
+                self.rotate()?;
+            }
+        }
+        self.dirty = true;
+        self.drop_flush = Some(Self::flush_if_dirty);
+        record.write(self.get_mut())?;
+        self.bytes_written += (RECORD_OVERHEAD + record.data.len()) as u64;
+        let padding = padding_len(self.bytes_written, self.alignment);
+        if padding > 0 {
+            self.get_mut().write_all(&ZEROS[..padding])?;
+            self.bytes_written += padding as u64;
+        }
+        self.unflushed_records += 1;
+        let due = match self.flush_policy {
+            FlushPolicy::Never => false,
+            FlushPolicy::EveryRecord => true,
+            FlushPolicy::EveryN(n) => n > 0 && self.unflushed_records >= n,
+            FlushPolicy::Interval(interval) => self.last_flush.elapsed() >= interval,
+        };
+        if due {
+            self.flush()?;
+        }
+        Ok(())
     }
 
-    /// Writes an `Event` to the output stream.
+    /// Moves on to a new event file, for [`Writer::new_rotating`].
+    fn rotate(&mut self) -> io::Result<()> {
+        self.flush()?;
+        let rotation = self
+            .rotation
+            .as_ref()
+            .expect("rotating without a rotation policy");
+        let (writer, path) = (rotation.open)(&rotation.run_directory)?;
+        // Dropping the old writer closes its file.
+        self.writer = Some(writer);
+        self.path = Some(path);
+        self.file_start = self.bytes_written;
+        // Every file needs its header, so this one isn't up to the filter.
+        let header = self.file_version_event()?;
+        self.write_unfiltered_event(&header, &mut Vec::new())?;
+        self.wrote_file_version = true;
+        self.rotation_header_bytes += self.bytes_written - self.file_start;
+        Ok(())
+    }
+
+    /// Writes an `Event` to the output stream, unless a [filter][Self::with_filter] rejects it.
     pub fn write_event(&mut self, event: &pb::Event) -> io::Result<()> {
-        let data = event.encode_to_vec();
-        let record = TfRecord::from_data(data);
-        self.write_record(&record)
+        self.write_event_into(event, &mut Vec::new())
+    }
+
+    /// Writes an `Event` to the output stream like [`write_event`][Self::write_event], but
+    /// serializes it into `scratch` (which is cleared first) instead of a fresh buffer. Reusing
+    /// the same buffer across calls avoids an allocation per event in tight loops.
+    pub fn write_event_into(&mut self, event: &pb::Event, scratch: &mut Vec<u8>) -> io::Result<()> {
+        if !self.accepts(event) {
+            return Ok(());
+        }
+        self.write_unfiltered_event(event, scratch)
     }
 
-    /// Writes a file version header event. This reads the current system time.
-    pub fn write_file_version(&mut self) -> io::Result<()> {
+    /// Whether the [filter][Self::with_filter], if any, lets `event` through.
+    fn accepts(&mut self, event: &pb::Event) -> bool {
+        self.filter.as_mut().map_or(true, |filter| filter(event))
+    }
+
+    /// Does the work of [`write_event_into`][Self::write_event_into] once the event has passed
+    /// the filter.
+    fn write_unfiltered_event(
+        &mut self,
+        event: &pb::Event,
+        scratch: &mut Vec<u8>,
+    ) -> io::Result<()> {
+        scratch.clear();
+        // `encode` can only fail if the buffer doesn't have enough room, and a `Vec` grows.
+        event.encode(scratch).expect("encoding into a Vec");
+        // Lend the buffer to the record, and take it back even if the write fails.
+        let record = TfRecord::from_data(std::mem::take(scratch));
+        let result = self.write_record(&record);
+        *scratch = record.data;
+        result?;
+        self.last_wall_time = Some(event.wall_time);
+        if let Some(dump) = &mut self.debug_dump {
+            writeln!(dump, "{}", crate::pbtxt::format_event(event))?;
+        }
+        Ok(())
+    }
+
+    /// Writes an already-serialized `Event` to the output stream, as when relaying events from
+    /// another process, without decoding it first. The bytes are written as-is: they bypass any
+    /// [filter][Self::with_filter] and [debug dump][Self::with_debug_dump], and aren't checked to
+    /// be a valid `Event`.
+    pub fn write_event_bytes(&mut self, data: Vec<u8>) -> io::Result<()> {
+        self.write_record(&TfRecord::from_data(data))
+    }
+
+    /// Writes a file version header event. This reads the current time from the writer's
+    /// [clock][Self::with_clock].
+    ///
+    /// # Errors
+    ///
+    /// Under [strict mode][Self::with_strict_file_version], fails if a file version event has
+    /// already been written.
+    pub fn write_file_version(&mut self) -> Result<(), WriteError> {
+        if self.strict_file_version && self.wrote_file_version {
+            let e = io::Error::new(io::ErrorKind::AlreadyExists, "file version already written");
+            return Err(e.into());
+        }
+        let event = self.file_version_event()?;
+        // A header that the filter drops doesn't count as written.
+        if self.accepts(&event) {
+            self.write_unfiltered_event(&event, &mut Vec::new())?;
+            self.wrote_file_version = true;
+        }
+        Ok(())
+    }
+
+    /// Builds a file version header event at the current time.
+    fn file_version_event(&mut self) -> Result<pb::Event, WriteError> {
         const FILE_VERSION: &str = "brain.Event:2";
         const WRITER: &str = "wchargin/rust-tensorboard-writer";
 
-        let mut event = pb::Event::default();
-        event.wall_time = time_f64(SystemTime::now())?;
-        event.what = Some(pb::event::What::FileVersion(FILE_VERSION.to_string()));
-        let mut source_metadata = pb::SourceMetadata::default();
-        source_metadata.writer = WRITER.to_string();
-        event.source_metadata = Some(source_metadata);
-        self.write_event(&event)
+        let now = (self.clock)();
+        Ok(pb::Event {
+            wall_time: self.encode_time(now)?,
+            what: Some(pb::event::What::FileVersion(FILE_VERSION.to_string())),
+            source_metadata: Some(pb::SourceMetadata {
+                writer: WRITER.to_string(),
+            }),
+            ..Default::default()
+        })
+    }
+
+    /// Writes a file version header event like [`write_file_version`][Self::write_file_version],
+    /// followed by a text summary recording this process's command line, for reproducibility.
+    ///
+    /// The command line is stored at step 0 under the tag [`CMDLINE_TAG`], with the arguments
+    /// separated by spaces (and quoted, if they contain whitespace or quotes).
+    pub fn write_file_version_with_cmdline(&mut self) -> Result<(), WriteError> {
+        self.write_file_version_with_cmdline_redacted(|_, arg| arg)
+    }
+
+    /// Like [`write_file_version_with_cmdline`][Self::write_file_version_with_cmdline], but passes
+    /// each argument through `redact` first, along with its index in the command line (where index
+    /// 0 is the program name). The callback can mask secrets, like API keys, by returning a
+    /// placeholder.
+    ///
+    /// ```
+    /// # use tensorboard_writer::TensorboardWriter;
+    /// let mut writer = TensorboardWriter::wrap(Vec::new());
+    /// writer
+    ///     .write_file_version_with_cmdline_redacted(|_, arg| {
+    ///         if arg.starts_with("--token=") {
+    ///             "--token=<redacted>".to_string()
+    ///         } else {
+    ///             arg
+    ///         }
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn write_file_version_with_cmdline_redacted<F>(
+        &mut self,
+        mut redact: F,
+    ) -> Result<(), WriteError>
+    where
+        F: FnMut(usize, String) -> String,
+    {
+        let args: Vec<String> = std::env::args()
+            .enumerate()
+            .map(|(i, arg)| quote_arg(&redact(i, arg)))
+            .collect();
+        self.write_file_version()?;
+        let summary = crate::SummaryBuilder::new()
+            .text(CMDLINE_TAG, &args.join(" "))
+            .build();
+        let now = (self.clock)();
+        self.write_summary(now, 0, summary)
+    }
+
+    /// Writes build information as a text summary at step 0, under the tag [`BUILD_INFO_TAG`].
+    ///
+    /// The fields are rendered as a two-column Markdown table, which TensorBoard's text dashboard
+    /// displays as a table.
+    pub fn write_build_info(&mut self, info: &BuildInfo) -> Result<(), WriteError> {
+        let fields = [
+            ("git_sha", &info.git_sha),
+            ("build_time", &info.build_time),
+            ("version", &info.version),
+        ];
+        let rows: Vec<Vec<String>> = fields
+            .iter()
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(name, value)| vec![name.to_string(), value.to_string()])
+            .collect();
+        let summary = crate::SummaryBuilder::new()
+            .text(BUILD_INFO_TAG, &markdown_table(&["field", "value"], &rows))
+            .build();
+        let now = (self.clock)();
+        self.write_summary(now, 0, summary)
+    }
+
+    /// Records this run's hyperparameter values for TensorBoard's HParams dashboard, as a session
+    /// start summary at step 0. Call this once per run, near the start.
+    ///
+    /// See the [`hparams`][crate::hparams] module for details.
+    pub fn write_hparams(&mut self, hparams: &[(&str, HParamValue)]) -> Result<(), WriteError> {
+        let now = (self.clock)();
+        // The HParams dashboard always reads these times as seconds since the epoch, even when
+        // the event's own wall time is relative to a time base.
+        let summary = hparams::session_start(hparams, time_f64(now)?);
+        self.write_summary(now, 0, summary)
+    }
+
+    /// Marks this run as finished for TensorBoard's HParams dashboard, as a session end summary
+    /// at step 0.
+    pub fn write_hparams_end(&mut self, status: hparams::Status) -> Result<(), WriteError> {
+        let now = (self.clock)();
+        let summary = hparams::session_end(status, time_f64(now)?);
+        self.write_summary(now, 0, summary)
+    }
+
+    /// Writes a graph for TensorBoard's Graphs dashboard, at step 0.
+    ///
+    /// The dashboard reads the graph from the event's `graph_def` field, which holds the
+    /// *serialized* `GraphDef` (as bytes) rather than the message itself; this takes care of the
+    /// encoding. Write one graph per run.
+    pub fn write_graph(
+        &mut self,
+        wall_time: SystemTime,
+        graph: pb::GraphDef,
+    ) -> Result<(), WriteError> {
+        let event = pb::Event {
+            wall_time: self.encode_time(wall_time)?,
+            what: Some(pb::event::What::GraphDef(graph.encode_to_vec().into())),
+            ..Default::default()
+        };
+        Ok(self.write_event(&event)?)
+    }
+
+    /// Writes a `SessionLog` event with the given status and message, marking a boundary in the
+    /// run.
+    ///
+    /// A [`Start`][SessionLogStatus::Start] event tells TensorBoard that training (re)started at
+    /// `step`, so it discards any data it has already loaded from this run at later steps. This is
+    /// what you want when resuming from a checkpoint after a crash: the steps that are recomputed
+    /// replace the ones logged before the crash, rather than overlapping them.
+    pub fn write_session_log(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        status: SessionLogStatus,
+        msg: &str,
+    ) -> Result<(), WriteError> {
+        let session_log = pb::SessionLog {
+            status: status.into(),
+            msg: msg.to_string(),
+            ..Default::default()
+        };
+        let event = pb::Event {
+            wall_time: self.encode_time(wall_time)?,
+            step,
+            what: Some(pb::event::What::SessionLog(session_log)),
+            ..Default::default()
+        };
+        Ok(self.write_event(&event)?)
+    }
+
+    /// Writes per-step `RunMetadata`, like device placement and step stats, for TensorBoard's
+    /// Graphs dashboard, which shows it under the given tag.
+    ///
+    /// As with [`write_graph`][Self::write_graph], the event holds the *serialized* metadata;
+    /// this takes care of the encoding. The wall time is read from the writer's
+    /// [clock][Self::with_clock].
+    pub fn write_run_metadata(
+        &mut self,
+        step: i64,
+        tag: &str,
+        metadata: pb::RunMetadata,
+    ) -> Result<(), WriteError> {
+        let now = (self.clock)();
+        let tagged = pb::TaggedRunMetadata {
+            tag: tag.to_string(),
+            run_metadata: metadata.encode_to_vec().into(),
+        };
+        let event = pb::Event {
+            wall_time: self.encode_time(now)?,
+            step,
+            what: Some(pb::event::What::TaggedRunMetadata(tagged)),
+            ..Default::default()
+        };
+        Ok(self.write_event(&event)?)
+    }
+
+    /// Defines the charts of TensorBoard's Custom Scalars dashboard, which can overlay several
+    /// scalar tags on one chart ([multiline][pb::MultilineChartContent]) or shade the area between
+    /// lower and upper bounds ([margin][pb::MarginChartContent]).
+    ///
+    /// Write a layout only once per run, e.g., right after the file version: the dashboard reads a
+    /// single layout per run, and merges the layouts of all runs. The layout is written under the
+    /// tag [`CUSTOM_SCALARS_TAG`] at step 0.
+    ///
+    /// ```
+    /// use tensorboard_writer::proto::tensorboard as pb;
+    /// use tensorboard_writer::TensorboardWriter;
+    ///
+    /// let chart = pb::Chart {
+    ///     title: "losses".to_string(),
+    ///     content: Some(pb::chart::Content::Multiline(pb::MultilineChartContent {
+    ///         tag: vec![r"loss/model_\d+".to_string()],
+    ///     })),
+    /// };
+    /// let layout = pb::Layout {
+    ///     category: vec![pb::Category {
+    ///         title: "ensemble".to_string(),
+    ///         chart: vec![chart],
+    ///         ..Default::default()
+    ///     }],
+    ///     ..Default::default()
+    /// };
+    /// let mut writer = TensorboardWriter::wrap(Vec::new());
+    /// writer.write_layout(layout).unwrap();
+    /// ```
+    pub fn write_layout(&mut self, layout: pb::Layout) -> Result<(), WriteError> {
+        let metadata = pb::SummaryMetadata {
+            plugin_data: Some(pb::summary_metadata::PluginData {
+                plugin_name: "custom_scalars".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let tensor = pb::TensorProto {
+            dtype: pb::DataType::DtString.into(),
+            tensor_shape: Some(pb::TensorShapeProto::default()),
+            string_val: vec![layout.encode_to_vec().into()],
+            ..Default::default()
+        };
+        let summary = pb::Summary {
+            value: vec![pb::summary::Value {
+                tag: CUSTOM_SCALARS_TAG.to_string(),
+                metadata: Some(metadata),
+                value: Some(pb::summary::value::Value::Tensor(tensor)),
+                ..Default::default()
+            }],
+        };
+        let now = (self.clock)();
+        self.write_summary(now, 0, summary)
     }
 
     /// Writes a summary to the output stream, wrapped in an `Event` with the given step and wall
@@ -128,16 +1220,1378 @@ impl<W: Write> Writer<W> {
     ///
     /// You may find it helpful to use the [`SummaryBuilder`][crate::SummaryBuilder] utility to
     /// construct the `summary` value.
+    ///
+    /// If [empty summaries are skipped][Self::with_skip_empty_summaries] and `summary` has no
+    /// values, this does nothing.
     pub fn write_summary(
         &mut self,
         wall_time: SystemTime,
         step: i64,
         summary: pb::Summary,
+    ) -> Result<(), WriteError> {
+        if self.skip_empty_summaries && summary.value.is_empty() {
+            return Ok(());
+        }
+        let wall_time = self.encode_time(wall_time)?;
+        Ok(self.write_summary_at(wall_time, step, summary)?)
+    }
+
+    /// Writes a summary like [`write_summary`][Self::write_summary], but with the wall time given
+    /// directly as the event's `wall_time` field: usually, seconds since the Unix epoch.
+    ///
+    /// This avoids a lossy round trip through `SystemTime` for timestamps that are already
+    /// floating-point seconds, e.g., from logs written by another system. The value is stored as
+    /// is, even if a [time base][Self::with_time_base] is set.
+    pub fn write_summary_at(
+        &mut self,
+        wall_time_secs: f64,
+        step: i64,
+        summary: pb::Summary,
     ) -> io::Result<()> {
-        let mut event = pb::Event::default();
-        event.wall_time = time_f64(wall_time)?;
-        event.step = step;
-        event.what = Some(pb::event::What::Summary(summary));
+        if self.skip_empty_summaries && summary.value.is_empty() {
+            return Ok(());
+        }
+        let event = pb::Event {
+            wall_time: wall_time_secs,
+            step,
+            what: Some(pb::event::What::Summary(summary)),
+            ..Default::default()
+        };
         self.write_event(&event)
     }
+
+    /// Writes a summary from its wire encoding, as from
+    /// [`SummaryBuilder::build_encoded`][crate::SummaryBuilder::build_encoded], wrapped in an
+    /// `Event` with the given step and wall time.
+    ///
+    /// The summary bytes are written as is, so values that this crate's protos don't know about
+    /// survive. Like [`write_event_bytes`][Self::write_event_bytes], this bypasses any
+    /// [filter][Self::with_filter] and [debug dump][Self::with_debug_dump], which only see
+    /// decoded events. If [empty summaries are skipped][Self::with_skip_empty_summaries] and
+    /// `summary` is empty, this does nothing.
+    pub fn write_summary_encoded(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: &[u8],
+    ) -> Result<(), WriteError> {
+        if self.skip_empty_summaries && summary.is_empty() {
+            return Ok(());
+        }
+        let event = pb::Event {
+            wall_time: self.encode_time(wall_time)?,
+            step,
+            ..Default::default()
+        };
+        // The summary is field 5 of `Event`, which comes after `wall_time` and `step` on the wire,
+        // just as `encode` would put it.
+        let mut data = event.encode_to_vec();
+        crate::summary::encode_length_delimited(5, summary, &mut data);
+        self.write_record(&TfRecord::from_data(data))?;
+        self.last_wall_time = Some(event.wall_time);
+        Ok(())
+    }
+
+    /// Writes several scalar summaries as a single event with the given step and wall time, as
+    /// with [`SummaryBuilder::scalar`][crate::SummaryBuilder::scalar] for each `(tag, value)`
+    /// pair.
+    ///
+    /// One event per step is smaller and faster to read than one event per scalar, since the
+    /// event framing and wall time aren't repeated for each value.
+    pub fn write_scalars(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        scalars: &[(&str, f32)],
+    ) -> Result<(), WriteError> {
+        let summary = scalars
+            .iter()
+            .fold(crate::SummaryBuilder::new(), |builder, &(tag, value)| {
+                builder.scalar(tag, value)
+            })
+            .build();
+        self.write_summary(wall_time, step, summary)
+    }
+
+    /// Writes a summary at the writer's current step, and then moves on to the next step. The wall
+    /// time is read from the writer's [clock][Self::with_clock].
+    ///
+    /// This is for scripts that log once per step and don't want to keep track of the step
+    /// themselves. The step starts at 0; use [`set_step`][Self::set_step] to start elsewhere,
+    /// e.g., when resuming from a checkpoint. Mixing this with explicit steps is allowed, but the
+    /// writer's step only changes when this or `set_step` is called.
+    pub fn write_summary_auto(&mut self, summary: pb::Summary) -> Result<(), WriteError> {
+        let now = (self.clock)();
+        self.write_summary(now, self.step, summary)?;
+        self.step += 1;
+        Ok(())
+    }
+
+    /// The step that the next call to [`write_summary_auto`][Self::write_summary_auto] will use.
+    pub fn current_step(&self) -> i64 {
+        self.step
+    }
+
+    /// Sets the step that the next call to [`write_summary_auto`][Self::write_summary_auto] will
+    /// use.
+    pub fn set_step(&mut self, step: i64) {
+        self.step = step;
+    }
+
+    /// Writes a summary like [`write_summary`][Self::write_summary], and returns the number of
+    /// bytes written to the output stream: the length of the framed TFRecord, plus any
+    /// [alignment][Self::with_alignment] padding, or 0 if the summary was skipped. If a
+    /// [rotating][Writer::new_rotating] writer moves to a new file first, the new file's header
+    /// isn't counted.
+    pub fn write_summary_counted(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> Result<usize, WriteError> {
+        let before = self.bytes_written - self.rotation_header_bytes;
+        self.write_summary(wall_time, step, summary)?;
+        Ok((self.bytes_written - self.rotation_header_bytes - before) as usize)
+    }
+
+    /// Writes a file version header event followed by a summary event, then flushes once.
+    ///
+    /// This gets the first data point of a run to TensorBoard as soon as possible, with a single
+    /// flush at startup rather than one per event.
+    pub fn write_file_version_and_summary(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> Result<(), WriteError> {
+        self.write_file_version()?;
+        self.write_summary(wall_time, step, summary)?;
+        Ok(self.flush()?)
+    }
+}
+
+impl<W: Write> SummaryWrite for Writer<W> {
+    fn write_summary(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> io::Result<()> {
+        Ok(Writer::write_summary(self, wall_time, step, summary)?)
+    }
+}
+
+impl<W: Write> AsyncSummaryWrite for Writer<W> {
+    fn write_summary(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> impl std::future::Future<Output = io::Result<()>> + Send {
+        std::future::ready(SummaryWrite::write_summary(self, wall_time, step, summary))
+    }
+}
+
+/// A handle to a [`Writer`] that can be shared between threads, e.g., so that data loaders and
+/// evaluation threads can log to the same run as the training loop.
+///
+/// Clones of a `SharedWriter` all write to the same underlying writer, which is protected by a
+/// mutex. Each call holds the lock just long enough to write (or flush) one event, so events from
+/// different threads are never interleaved mid-record, but they're written in whatever order the
+/// threads take the lock. Logging from many threads at a high rate will contend on the lock; if
+/// that shows up in profiles, give each thread its own event file in the run directory instead,
+/// which TensorBoard merges when reading.
+///
+/// ```
+/// use std::time::SystemTime;
+///
+/// use tensorboard_writer::{SharedWriter, SummaryBuilder, TensorboardWriter};
+///
+/// let writer = SharedWriter::new(TensorboardWriter::wrap(Vec::new()));
+/// let handles: Vec<_> = (0..4)
+///     .map(|i| {
+///         let writer = writer.clone();
+///         std::thread::spawn(move || {
+///             let summ = SummaryBuilder::new().scalar("loader/queue_len", i as f32).build();
+///             writer.write_summary(SystemTime::now(), 0, summ).unwrap();
+///         })
+///     })
+///     .collect();
+/// handles.into_iter().for_each(|h| h.join().unwrap());
+/// writer.flush().unwrap();
+/// ```
+pub struct SharedWriter<W> {
+    inner: Arc<Mutex<Writer<W>>>,
+}
+
+impl<W> Clone for SharedWriter<W> {
+    fn clone(&self) -> Self {
+        SharedWriter {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<W: Write> SharedWriter<W> {
+    /// Wraps a writer for sharing.
+    pub fn new(writer: Writer<W>) -> Self {
+        SharedWriter {
+            inner: Arc::new(Mutex::new(writer)),
+        }
+    }
+
+    /// Locks the underlying writer, for calling methods that this type doesn't forward or making
+    /// several writes without other threads' events in between.
+    ///
+    /// # Errors
+    ///
+    /// Fails if another thread panicked while holding the lock, since the writer may then have
+    /// been left in the middle of a record.
+    pub fn lock(&self) -> io::Result<MutexGuard<'_, Writer<W>>> {
+        self.inner
+            .lock()
+            .map_err(|_| io::Error::other("shared writer poisoned by a panic"))
+    }
+
+    /// Writes a summary like [`Writer::write_summary`].
+    pub fn write_summary(
+        &self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> Result<(), WriteError> {
+        self.lock()?.write_summary(wall_time, step, summary)
+    }
+
+    /// Writes an event like [`Writer::write_event`].
+    pub fn write_event(&self, event: &pb::Event) -> io::Result<()> {
+        self.lock()?.write_event(event)
+    }
+
+    /// Flushes the underlying writer like [`Writer::flush`].
+    pub fn flush(&self) -> io::Result<()> {
+        self.lock()?.flush()
+    }
+
+    /// Unwraps the underlying writer, if this is the last handle to it. Otherwise, returns the
+    /// handle unchanged.
+    pub fn into_inner(self) -> Result<Writer<W>, Self> {
+        match Arc::try_unwrap(self.inner) {
+            Ok(mutex) => Ok(mutex.into_inner().unwrap_or_else(PoisonError::into_inner)),
+            Err(inner) => Err(SharedWriter { inner }),
+        }
+    }
+}
+
+impl<W: Write> SummaryWrite for SharedWriter<W> {
+    fn write_summary(
+        &mut self,
+        wall_time: SystemTime,
+        step: i64,
+        summary: pb::Summary,
+    ) -> io::Result<()> {
+        Ok(SharedWriter::write_summary(self, wall_time, step, summary)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_reader::EventReader;
+    use crate::temp_dir::TempDir;
+
+    /// A file wrapper that counts how many times it's been synced.
+    struct CountingFile {
+        file: File,
+        syncs: usize,
+    }
+
+    impl Write for CountingFile {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.file.write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            self.file.flush()
+        }
+    }
+
+    impl SyncAll for CountingFile {
+        fn sync_all(&mut self) -> io::Result<()> {
+            self.syncs += 1;
+            self.file.sync_all()
+        }
+    }
+
+    /// Counts how many times the underlying buffer has been flushed.
+    #[derive(Default)]
+    struct FlushCounter {
+        buf: Vec<u8>,
+        flushes: usize,
+    }
+
+    impl Write for FlushCounter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.buf.write(buf)
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_sync_policy_every_n() {
+        let dir = TempDir::create();
+        let file = File::create(dir.path().join("events.out.tfevents.test")).unwrap();
+        let mut writer =
+            Writer::wrap(CountingFile { file, syncs: 0 }).with_sync_policy(SyncPolicy::EveryN(3));
+        let mut syncs = Vec::new();
+        for _ in 0..7 {
+            writer.write_file_version().unwrap();
+            writer.flush().unwrap();
+            syncs.push(writer.get_ref().syncs);
+        }
+        assert_eq!(syncs, vec![0, 0, 1, 1, 1, 2, 2]);
+    }
+
+    #[test]
+    fn test_autoflush() {
+        let flushes = |policy| {
+            let mut writer = Writer::wrap(FlushCounter::default()).with_autoflush(policy);
+            let mut flushes = Vec::new();
+            for _ in 0..5 {
+                writer.write_file_version().unwrap();
+                flushes.push(writer.get_ref().flushes);
+            }
+            flushes
+        };
+        assert_eq!(flushes(FlushPolicy::Never), vec![0, 0, 0, 0, 0]);
+        assert_eq!(flushes(FlushPolicy::EveryRecord), vec![1, 2, 3, 4, 5]);
+        assert_eq!(flushes(FlushPolicy::EveryN(2)), vec![0, 1, 1, 2, 2]);
+        assert_eq!(flushes(FlushPolicy::EveryN(0)), vec![0, 0, 0, 0, 0]);
+        assert_eq!(
+            flushes(FlushPolicy::Interval(Duration::ZERO)),
+            vec![1, 2, 3, 4, 5]
+        );
+        assert_eq!(
+            flushes(FlushPolicy::Interval(Duration::from_secs(3600))),
+            vec![0, 0, 0, 0, 0]
+        );
+
+        // A manual flush restarts the count.
+        let mut writer =
+            Writer::wrap(FlushCounter::default()).with_autoflush(FlushPolicy::EveryN(2));
+        writer.write_file_version().unwrap();
+        writer.flush().unwrap();
+        writer.write_file_version().unwrap();
+        assert_eq!(writer.get_ref().flushes, 1);
+    }
+
+    #[test]
+    fn test_finish() {
+        let mut writer = Writer::wrap(FlushCounter::default());
+        writer.write_file_version().unwrap();
+        let inner = writer.finish().unwrap();
+        assert_eq!(inner.flushes, 1);
+        assert_eq!(EventReader::new(&inner.buf[..]).count(), 1);
+    }
+
+    #[test]
+    fn test_wrap_without_write() {
+        // Wrapping and unwrapping don't need `W: Write`.
+        let writer = Writer::wrap("not a writer");
+        assert_eq!(*writer.get_ref(), "not a writer");
+        assert_eq!(writer.into_inner(), "not a writer");
+    }
+
+    #[test]
+    fn test_drop_flushes_wrapped_writer() {
+        let mut inner = FlushCounter::default();
+        let mut writer = Writer::wrap(&mut inner);
+        writer.write_file_version().unwrap();
+        drop(writer);
+        assert_eq!(inner.flushes, 1);
+
+        // Nothing to flush, and an unwrapped writer is left alone.
+        drop(Writer::wrap(&mut inner));
+        let mut writer = Writer::wrap(&mut inner);
+        writer.write_file_version().unwrap();
+        writer.into_inner();
+        assert_eq!(inner.flushes, 1);
+    }
+
+    #[test]
+    fn test_drop_flushes_file() {
+        let dir = TempDir::create();
+        let mut writer = Writer::new_initialized(dir.path()).unwrap();
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        writer.write_summary(SystemTime::now(), 1, summ).unwrap();
+        let path = writer.path().unwrap().to_path_buf();
+        drop(writer);
+        assert_eq!(EventReader::open(path).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_sync_policy_never() {
+        let dir = TempDir::create();
+        let file = File::create(dir.path().join("events.out.tfevents.test")).unwrap();
+        let mut writer =
+            Writer::wrap(CountingFile { file, syncs: 0 }).with_sync_policy(SyncPolicy::Never);
+        for _ in 0..5 {
+            writer.flush().unwrap();
+        }
+        assert_eq!(writer.get_ref().syncs, 0);
+    }
+
+    #[test]
+    fn test_write_scalars() {
+        let mut writer = Writer::wrap(Vec::new());
+        writer
+            .write_scalars(std::time::UNIX_EPOCH, 3, &[("loss", 0.5), ("acc", 0.9)])
+            .unwrap();
+        let events: Vec<pb::Event> = EventReader::new(&writer.into_inner()[..])
+            .map(|e| e.unwrap())
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].step, 3);
+        let expected = crate::SummaryBuilder::new()
+            .scalar("loss", 0.5)
+            .scalar("acc", 0.9)
+            .build();
+        assert_eq!(events[0].what, Some(pb::event::What::Summary(expected)));
+    }
+
+    #[test]
+    fn test_write_session_log() {
+        let mut writer = Writer::wrap(Vec::new());
+        writer
+            .write_session_log(SystemTime::now(), 100, SessionLogStatus::Start, "resumed")
+            .unwrap();
+        let events: Vec<pb::Event> = EventReader::new(&writer.into_vec()[..])
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].step, 100);
+        let expected = pb::SessionLog {
+            status: SessionLogStatus::Start as i32,
+            checkpoint_path: String::new(),
+            msg: "resumed".to_string(),
+        };
+        assert_eq!(events[0].what, Some(pb::event::What::SessionLog(expected)));
+    }
+
+    #[test]
+    fn test_write_run_metadata() {
+        let metadata = pb::RunMetadata {
+            step_stats: Some(pb::StepStats {
+                dev_stats: vec![pb::DeviceStepStats {
+                    device: "/device:GPU:0".to_string(),
+                    node_stats: vec![pb::NodeExecStats {
+                        node_name: "matmul".to_string(),
+                        all_start_micros: 1000,
+                        all_end_rel_micros: 25,
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+            }),
+            ..Default::default()
+        };
+        let mut writer = Writer::wrap(Vec::new());
+        writer
+            .write_run_metadata(7, "step_7", metadata.clone())
+            .unwrap();
+        let events: Vec<pb::Event> = EventReader::new(&writer.into_vec()[..])
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(events[0].step, 7);
+        let tagged = match &events[0].what {
+            Some(pb::event::What::TaggedRunMetadata(t)) => t,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(tagged.tag, "step_7");
+        assert_eq!(
+            pb::RunMetadata::decode(&tagged.run_metadata[..]).unwrap(),
+            metadata
+        );
+    }
+
+    #[test]
+    fn test_shared_writer() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SharedWriter<BufWriter<File>>>();
+
+        let writer = SharedWriter::new(Writer::wrap(Vec::new()));
+        let threads: Vec<_> = (0..4)
+            .map(|i| {
+                let writer = writer.clone();
+                std::thread::spawn(move || {
+                    for step in 0..10 {
+                        let summ = crate::SummaryBuilder::new()
+                            .scalar(&format!("thread{}", i), 1.0)
+                            .build();
+                        writer.write_summary(SystemTime::now(), step, summ).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        writer.flush().unwrap();
+        let bytes = writer.into_inner().ok().unwrap().into_vec();
+        let events: Vec<pb::Event> = EventReader::new(&bytes[..]).map(Result::unwrap).collect();
+        assert_eq!(events.len(), 40);
+
+        let writer = SharedWriter::new(Writer::wrap(Vec::<u8>::new()));
+        let other = writer.clone();
+        let writer = writer.into_inner().err().unwrap();
+        drop(other);
+        assert!(writer.into_inner().is_ok());
+    }
+
+    #[test]
+    fn test_write_summary_auto() {
+        let mut writer = Writer::wrap(Vec::new());
+        assert_eq!(writer.current_step(), 0);
+        for _ in 0..3 {
+            let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+            writer.write_summary_auto(summ).unwrap();
+        }
+        assert_eq!(writer.current_step(), 3);
+        writer.set_step(100);
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        writer.write_summary_auto(summ).unwrap();
+        assert_eq!(writer.current_step(), 101);
+        let steps: Vec<i64> = EventReader::new(&writer.into_vec()[..])
+            .map(|e| e.unwrap().step)
+            .collect();
+        assert_eq!(steps, vec![0, 1, 2, 100]);
+    }
+
+    #[test]
+    fn test_write_summary_at() {
+        let mut writer = Writer::wrap(Vec::new()).with_time_base(SystemTime::now());
+        let wall_time = 1_600_000_000.123_456_7;
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        writer.write_summary_at(wall_time, 5, summ).unwrap();
+        let events: Vec<pb::Event> = EventReader::new(&writer.into_vec()[..])
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!((events[0].wall_time, events[0].step), (wall_time, 5));
+    }
+
+    #[test]
+    fn test_summary_write_generic() {
+        fn log_losses<S: SummaryWrite>(sink: &mut S) -> io::Result<()> {
+            for step in 0..3 {
+                let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+                sink.write_summary(SystemTime::now(), step, summ)?;
+            }
+            Ok(())
+        }
+        let mut writer = Writer::wrap(Vec::new());
+        log_losses(&mut writer).unwrap();
+        let steps: Vec<i64> = EventReader::new(&writer.into_inner()[..])
+            .map(|e| e.unwrap().step)
+            .collect();
+        assert_eq!(steps, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_civil_from_days() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19358), (2023, 1, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+    }
+
+    #[test]
+    fn test_auto_run_name() {
+        let time = std::time::UNIX_EPOCH + Duration::from_secs(1672583400);
+        let mut expected = OsString::from("20230101-143000.");
+        expected.push(hostname::get().unwrap_or_default());
+        assert_eq!(auto_run_name(time), expected);
+    }
+
+    #[test]
+    fn test_new_auto_run() {
+        let dir = TempDir::create();
+        let mut writer = Writer::new_auto_run(dir.path()).unwrap();
+        writer.write_file_version().unwrap();
+        let runs: Vec<String> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert_eq!(runs.len(), 1);
+        let (timestamp, host) = runs[0].split_once('.').unwrap();
+        let (date, time) = timestamp.split_once('-').unwrap();
+        assert_eq!(date.len(), 8);
+        assert_eq!(time.len(), 6);
+        assert!(date.chars().chain(time.chars()).all(|c| c.is_ascii_digit()));
+        assert_eq!(host, hostname::get().unwrap().to_str().unwrap());
+    }
+
+    #[test]
+    fn test_write_file_version_and_summary() {
+        let mut writer = Writer::wrap(FlushCounter::default());
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        writer
+            .write_file_version_and_summary(SystemTime::now(), 7, summ)
+            .unwrap();
+        let sink = writer.into_inner();
+        assert_eq!(sink.flushes, 1);
+        let events: Vec<pb::Event> = EventReader::new(&sink.buf[..])
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(
+            events[0].what,
+            Some(pb::event::What::FileVersion(_))
+        ));
+        assert_eq!(events[1].step, 7);
+        assert!(matches!(events[1].what, Some(pb::event::What::Summary(_))));
+    }
+
+    #[test]
+    fn test_write_to_borrowed_vec() {
+        let mut buf = Vec::new();
+        {
+            let mut writer = Writer::wrap(&mut buf);
+            writer.write_file_version().unwrap();
+            let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+            writer.write_summary(SystemTime::now(), 3, summ).unwrap();
+        }
+        let steps: Vec<i64> = EventReader::new(&buf[..])
+            .map(|e| e.unwrap().step)
+            .collect();
+        assert_eq!(steps, vec![0, 3]);
+    }
+
+    #[test]
+    fn test_into_vec() {
+        let mut writer = Writer::wrap(Vec::new());
+        writer.write_file_version().unwrap();
+        let buf = writer.into_vec();
+        let events: Vec<pb::Event> = EventReader::new(&buf[..]).map(Result::unwrap).collect();
+        assert_eq!(events.len(), 1);
+    }
+
+    #[test]
+    fn test_debug_dump() {
+        let dir = TempDir::create();
+        let dump_path = dir.path().join("events.pbtxt");
+        let mut writer = Writer::wrap(Vec::new())
+            .with_debug_dump(BufWriter::new(File::create(&dump_path).unwrap()));
+        writer.write_file_version().unwrap();
+        let summ = crate::SummaryBuilder::new()
+            .scalar("loss", 0.5)
+            .scalar("accuracy", 0.75)
+            .build();
+        writer.write_summary(SystemTime::now(), 1, summ).unwrap();
+        writer.flush().unwrap();
+
+        let dump = std::fs::read_to_string(&dump_path).unwrap();
+        assert!(dump.contains("file_version: \"brain.Event:2\""), "{}", dump);
+        assert!(dump.contains("tag: \"loss\""), "{}", dump);
+        assert!(dump.contains("tag: \"accuracy\""), "{}", dump);
+        let events = EventReader::new(&writer.into_inner()[..]).count();
+        assert_eq!(events, 2);
+    }
+
+    /// Lists the names of the files in a directory.
+    fn file_names(dir: &Path) -> Vec<String> {
+        std::fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_run_directory() {
+        let dir = TempDir::create();
+        let target = dir.path().join("scratch");
+        std::fs::create_dir(&target).unwrap();
+        let run = dir.path().join("run");
+        std::os::unix::fs::symlink(&target, &run).unwrap();
+
+        let mut writer = Writer::new(&run).unwrap();
+        writer.write_file_version().unwrap();
+        writer.flush().unwrap();
+        let names = file_names(&target);
+        assert_eq!(names.len(), 1);
+        assert!(names[0].starts_with("events.out.tfevents."), "{:?}", names);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_dangling_symlinked_run_directory() {
+        let dir = TempDir::create();
+        let run = dir.path().join("run");
+        // relative to `dir`, where the link lives
+        std::os::unix::fs::symlink("scratch/run", &run).unwrap();
+
+        Writer::new(&run).unwrap();
+        let names = file_names(&dir.path().join("scratch/run"));
+        assert_eq!(names.len(), 1);
+        assert!(std::fs::symlink_metadata(&run)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+    }
+
+    /// Extracts the tag and string from an event with a single-value text summary.
+    fn text_summary(event: &pb::Event) -> (&str, String) {
+        let value = match &event.what {
+            Some(pb::event::What::Summary(summ)) => &summ.value[0],
+            other => panic!("{:?}", other),
+        };
+        let tensor = match &value.value {
+            Some(pb::summary::value::Value::Tensor(t)) => t,
+            other => panic!("{:?}", other),
+        };
+        let text = String::from_utf8(tensor.string_val[0].to_vec()).unwrap();
+        (&value.tag, text)
+    }
+
+    #[test]
+    fn test_write_file_version_with_cmdline() {
+        let args: Vec<String> = std::env::args().collect();
+        let mut writer = Writer::wrap(Vec::new());
+        writer
+            .write_file_version_with_cmdline_redacted(|i, arg| {
+                if i == 0 {
+                    arg
+                } else {
+                    "<redacted>".to_string()
+                }
+            })
+            .unwrap();
+        let events: Vec<pb::Event> = EventReader::new(&writer.into_vec()[..])
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(events.len(), 2);
+        let (tag, text) = text_summary(&events[1]);
+        assert_eq!(tag, CMDLINE_TAG);
+        let mut expected = vec![quote_arg(&args[0])];
+        expected.resize(args.len(), "<redacted>".to_string());
+        assert_eq!(text, expected.join(" "));
+    }
+
+    #[test]
+    fn test_write_build_info() {
+        let mut writer = Writer::wrap(Vec::new());
+        let info = BuildInfo {
+            git_sha: "0123abcd".to_string(),
+            version: "1.2.3".to_string(),
+            ..Default::default()
+        };
+        writer.write_build_info(&info).unwrap();
+        let events: Vec<pb::Event> = EventReader::new(&writer.into_vec()[..])
+            .map(Result::unwrap)
+            .collect();
+        let (tag, text) = text_summary(&events[0]);
+        assert_eq!(tag, BUILD_INFO_TAG);
+        assert_eq!(
+            text,
+            "| field | value |\n| --- | --- |\n| git_sha | 0123abcd |\n| version | 1.2.3 |\n"
+        );
+    }
+
+    #[test]
+    fn test_write_hparams() {
+        let t = std::time::UNIX_EPOCH + Duration::from_secs(100);
+        let mut writer = Writer::wrap(Vec::new()).with_clock(move || t);
+        writer
+            .write_hparams(&[("lr", HParamValue::F64(0.01)), ("adam", true.into())])
+            .unwrap();
+        writer.write_hparams_end(hparams::Status::Success).unwrap();
+        let events: Vec<pb::Event> = EventReader::new(&writer.into_vec()[..])
+            .map(Result::unwrap)
+            .collect();
+        let tags: Vec<(f64, &str)> = events
+            .iter()
+            .map(|e| match &e.what {
+                Some(pb::event::What::Summary(s)) => (e.wall_time, s.value[0].tag.as_str()),
+                other => panic!("{:?}", other),
+            })
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                (100.0, hparams::SESSION_START_INFO_TAG),
+                (100.0, hparams::SESSION_END_INFO_TAG),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_hparams_with_time_base() {
+        use crate::proto::tensorboard::hparams as hp;
+        let base = std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let t = base + Duration::from_secs(5);
+        let mut writer = Writer::wrap(Vec::new())
+            .with_time_base(base)
+            .with_clock(move || t);
+        writer
+            .write_hparams(&[("lr", HParamValue::F64(0.01))])
+            .unwrap();
+        writer.write_hparams_end(hparams::Status::Success).unwrap();
+        let times: Vec<(f64, f64)> = EventReader::new(&writer.into_vec()[..])
+            .map(|e| {
+                let e = e.unwrap();
+                let content = match &e.what {
+                    Some(pb::event::What::Summary(s)) => {
+                        let metadata = s.value[0].metadata.as_ref().unwrap();
+                        metadata.plugin_data.as_ref().unwrap().content.clone()
+                    }
+                    other => panic!("{:?}", other),
+                };
+                let data = hp::HParamsPluginData::decode(&content[..]).unwrap();
+                let secs = match data.data.unwrap() {
+                    hp::h_params_plugin_data::Data::SessionStartInfo(i) => i.start_time_secs,
+                    hp::h_params_plugin_data::Data::SessionEndInfo(i) => i.end_time_secs,
+                    other => panic!("{:?}", other),
+                };
+                (e.wall_time, secs)
+            })
+            .collect();
+        // The events are relative to the base, but the session times are absolute.
+        assert_eq!(times, vec![(5.0, 1_700_000_005.0), (5.0, 1_700_000_005.0)]);
+    }
+
+    #[test]
+    fn test_quote_arg() {
+        assert_eq!(quote_arg("--lr=0.1"), "--lr=0.1");
+        assert_eq!(quote_arg(""), "''");
+        assert_eq!(quote_arg("a b"), "'a b'");
+        assert_eq!(quote_arg("it's"), r"'it'\''s'");
+    }
+
+    #[test]
+    fn test_flush_if_dirty() {
+        let mut writer = Writer::wrap(FlushCounter::default());
+        writer.flush_if_dirty().unwrap();
+        assert_eq!(writer.get_ref().flushes, 0);
+        writer.write_file_version().unwrap();
+        writer.flush().unwrap();
+        writer.flush_if_dirty().unwrap();
+        assert_eq!(writer.get_ref().flushes, 1);
+        writer.write_file_version().unwrap();
+        writer.flush_if_dirty().unwrap();
+        writer.flush_if_dirty().unwrap();
+        assert_eq!(writer.get_ref().flushes, 2);
+    }
+
+    #[test]
+    fn test_new_preallocated() {
+        let dir = TempDir::create();
+        let mut writer = Writer::new_preallocated(dir.path(), 4096).unwrap();
+        writer.write_file_version().unwrap();
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        writer.write_summary(SystemTime::now(), 1, summ).unwrap();
+        writer.flush().unwrap();
+        let names = file_names(dir.path());
+        let path = dir.path().join(&names[0]);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 4096);
+
+        writer.close().unwrap();
+        let events: Vec<pb::Event> = EventReader::open(&path)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(events.len(), 2);
+        let expected_len: usize = events
+            .iter()
+            // 12-byte header and 4-byte footer per record
+            .map(|e| 12 + e.encoded_len() + 4)
+            .sum();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), expected_len as u64);
+    }
+
+    #[test]
+    fn test_open_append() {
+        let dir = TempDir::create();
+        let mut writer = Writer::new_initialized(dir.path()).unwrap();
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        writer.write_summary(SystemTime::now(), 1, summ).unwrap();
+        writer.close().unwrap();
+        let path = dir.path().join(&file_names(dir.path())[0]);
+
+        let mut writer = Writer::open_append(&path).unwrap();
+        let summ = crate::SummaryBuilder::new().scalar("loss", 0.5).build();
+        writer.write_summary(SystemTime::now(), 2, summ).unwrap();
+        writer.close().unwrap();
+        // Closing without writing anything must not truncate the file.
+        Writer::open_append(&path).unwrap().close().unwrap();
+
+        let events: Vec<pb::Event> = EventReader::open(&path)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        let steps: Vec<i64> = events.iter().map(|e| e.step).collect();
+        assert_eq!(steps, vec![0, 1, 2]);
+        assert!(matches!(
+            events[0].what,
+            Some(pb::event::What::FileVersion(_))
+        ));
+        assert_eq!(file_names(dir.path()).len(), 1);
+
+        assert!(Writer::open_append(dir.path().join("nope")).is_err());
+    }
+
+    #[test]
+    fn test_path() {
+        let dir = TempDir::create();
+        let writer = Writer::new(dir.path()).unwrap();
+        let path = writer.path().unwrap().to_path_buf();
+        assert_eq!(path, dir.path().join(&file_names(dir.path())[0]));
+        drop(writer);
+        assert_eq!(
+            Writer::open_append(&path).unwrap().path(),
+            Some(path.as_path())
+        );
+        assert_eq!(Writer::wrap(Vec::<u8>::new()).path(), None);
+    }
+
+    #[test]
+    fn test_new_rotating() {
+        let dir = TempDir::create();
+        let mut writer = Writer::new_rotating(dir.path(), 200).unwrap();
+        let first_path = writer.path().unwrap().to_path_buf();
+        for step in 0..20 {
+            let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+            writer.write_summary(SystemTime::now(), step, summ).unwrap();
+        }
+        assert_ne!(writer.path().unwrap(), first_path);
+        drop(writer);
+
+        let names = file_names(dir.path());
+        assert!(names.len() > 1, "{:?}", names);
+        // Later files sort after earlier ones.
+        let mut names = names;
+        names.sort();
+        let mut steps = Vec::new();
+        for name in &names {
+            let path = dir.path().join(name);
+            assert!(std::fs::metadata(&path).unwrap().len() < 200 + 100);
+            let events: Vec<pb::Event> = EventReader::open(&path)
+                .unwrap()
+                .map(Result::unwrap)
+                .collect();
+            assert!(
+                matches!(events[0].what, Some(pb::event::What::FileVersion(_))),
+                "{}: {:?}",
+                name,
+                events[0]
+            );
+            steps.extend(events[1..].iter().map(|e| e.step));
+        }
+        assert_eq!(steps, (0..20).collect::<Vec<i64>>());
+    }
+
+    #[test]
+    fn test_new_rotating_with_filter() {
+        // Rotated files get their header even if the filter would drop it.
+        let dir = TempDir::create();
+        let mut writer = Writer::new_rotating(dir.path(), 1)
+            .unwrap()
+            .with_filter(|event| !matches!(event.what, Some(pb::event::What::FileVersion(_))));
+        for step in 0..3 {
+            let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+            writer.write_summary(SystemTime::now(), step, summ).unwrap();
+        }
+        drop(writer);
+        let names = file_names(dir.path());
+        // The first file, with just its header, and one per summary.
+        assert_eq!(names.len(), 4, "{:?}", names);
+        for name in &names {
+            let first = EventReader::open(dir.path().join(name))
+                .unwrap()
+                .next()
+                .unwrap()
+                .unwrap();
+            assert!(matches!(first.what, Some(pb::event::What::FileVersion(_))));
+        }
+    }
+
+    #[test]
+    fn test_new_rotating_zero_max_bytes() {
+        let dir = TempDir::create();
+        let err = Writer::new_rotating(dir.path(), 0).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(file_names(dir.path()), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_new_rotating_tiny_max_bytes() {
+        // Every record fills a file, so each summary gets a file of its own after the header.
+        let dir = TempDir::create();
+        let mut writer = Writer::new_rotating(dir.path(), 1).unwrap();
+        let summ = || crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        let time = std::time::UNIX_EPOCH + Duration::from_secs(1);
+        for step in 0..3 {
+            let len = writer.write_summary_counted(time, step, summ()).unwrap();
+            let mut expected = Writer::wrap(Vec::<u8>::new());
+            expected.write_summary(time, step, summ()).unwrap();
+            assert_eq!(len, expected.into_vec().len());
+        }
+        drop(writer);
+        assert_eq!(file_names(dir.path()).len(), 4);
+    }
+
+    #[test]
+    fn test_close_with_mtime() {
+        let dir = TempDir::create();
+        let mut writer = Writer::new(dir.path()).unwrap();
+        let wall_time = std::time::UNIX_EPOCH + Duration::from_secs(1_500_000_000);
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        writer.write_summary(wall_time, 1, summ).unwrap();
+        writer.close_with_mtime().unwrap();
+        let path = dir.path().join(&file_names(dir.path())[0]);
+        let mtime = std::fs::metadata(&path).unwrap().modified().unwrap();
+        assert_eq!(mtime, wall_time);
+    }
+
+    #[test]
+    fn test_time_before_epoch() {
+        let mut writer = Writer::wrap(Vec::new());
+        let before = std::time::UNIX_EPOCH - Duration::from_secs(1);
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        match writer.write_summary(before, 0, summ) {
+            Err(WriteError::TimeBeforeEpoch(t)) => assert_eq!(t, before),
+            other => panic!("{:?}", other),
+        }
+        assert!(writer.into_vec().is_empty());
+        let e = io::Error::from(WriteError::TimeBeforeEpoch(before));
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_with_time_base() {
+        let base = std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut writer = Writer::wrap(Vec::new()).with_time_base(base);
+        for (step, offset) in [(0, Duration::from_micros(1)), (1, Duration::from_secs(90))] {
+            let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+            writer.write_summary(base + offset, step, summ).unwrap();
+        }
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        let before = base - Duration::from_millis(250);
+        writer.write_summary(before, 2, summ).unwrap();
+        let times: Vec<f64> = EventReader::new(&writer.into_vec()[..])
+            .map(|e| e.unwrap().wall_time)
+            .collect();
+        assert_eq!(times, vec![1e-6, 90.0, -0.25]);
+    }
+
+    #[test]
+    fn test_deterministic_output() {
+        let dir = TempDir::create();
+        let epoch = std::time::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let write_run = |run: &str| {
+            let mut writer =
+                Writer::new_with_file_name(dir.path().join(run), "events.out.tfevents.golden")
+                    .unwrap()
+                    .with_clock(move || epoch);
+            writer.write_file_version().unwrap();
+            for step in 0..3 {
+                let summ = crate::SummaryBuilder::new()
+                    .scalar("loss", step as f32)
+                    .histogram("w", 3, &[0.0, step as f64])
+                    .build();
+                let wall_time = epoch + Duration::from_secs(step as u64);
+                writer.write_summary(wall_time, step, summ).unwrap();
+            }
+            writer.close().unwrap();
+            std::fs::read(dir.path().join(run).join("events.out.tfevents.golden")).unwrap()
+        };
+        let first = write_run("a");
+        assert!(!first.is_empty());
+        assert_eq!(first, write_run("b"));
+        let events: Vec<pb::Event> = EventReader::new(&first[..]).map(Result::unwrap).collect();
+        assert_eq!(events[0].wall_time, 1_600_000_000.0);
+    }
+
+    #[test]
+    fn test_new_initialized() {
+        let dir = TempDir::create();
+        let writer = Writer::new_initialized(dir.path()).unwrap();
+        // The header is already on disk, under the final name.
+        let names = file_names(dir.path());
+        assert_eq!(names.len(), 1, "{:?}", names);
+        assert!(names[0].contains("tfevents"));
+        let path = dir.path().join(&names[0]);
+        assert_eq!(writer.path(), Some(path.as_path()));
+        let events: Vec<pb::Event> = EventReader::open(path)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].what,
+            Some(pb::event::What::FileVersion("brain.Event:2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_with_alignment() {
+        let mut writer = Writer::wrap(Vec::new()).with_alignment(8);
+        for step in 0..5 {
+            let summ = crate::SummaryBuilder::new()
+                .scalar(&"x".repeat(step as usize), 1.0)
+                .build();
+            writer.write_summary(SystemTime::now(), step, summ).unwrap();
+        }
+        let buf = writer.into_vec();
+        assert_eq!(buf.len() % 8, 0);
+        let steps: Vec<i64> = EventReader::new(&buf[..])
+            .with_alignment(8)
+            .map(|e| e.unwrap().step)
+            .collect();
+        assert_eq!(steps, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_padding_len() {
+        assert_eq!(padding_len(0, 8), 0);
+        assert_eq!(padding_len(5, 8), 3);
+        assert_eq!(padding_len(16, 8), 0);
+        assert_eq!(padding_len(17, 1), 0);
+        assert_eq!(padding_len(17, 0), 0);
+    }
+
+    #[test]
+    fn test_write_event_into() {
+        let mut writer = Writer::wrap(Vec::new());
+        let mut scratch = Vec::new();
+        let mut expected = Writer::wrap(Vec::new());
+        let mut buffer_ptr = None;
+        for step in 0..3 {
+            let event = pb::Event {
+                step,
+                what: Some(pb::event::What::FileVersion("x".repeat(3 - step as usize))),
+                ..Default::default()
+            };
+            writer.write_event_into(&event, &mut scratch).unwrap();
+            assert_eq!(scratch, event.encode_to_vec());
+            // Events get smaller, so the first allocation is reused.
+            assert_eq!(
+                *buffer_ptr.get_or_insert(scratch.as_ptr()),
+                scratch.as_ptr()
+            );
+            expected.write_event(&event).unwrap();
+        }
+        assert_eq!(writer.into_vec(), expected.into_vec());
+    }
+
+    #[test]
+    fn test_write_event_bytes() {
+        let event = pb::Event {
+            step: 7,
+            what: Some(pb::event::What::FileVersion("brain.Event:2".to_string())),
+            ..Default::default()
+        };
+        let mut writer = Writer::wrap(Vec::new());
+        writer.write_event_bytes(event.encode_to_vec()).unwrap();
+        let mut expected = Writer::wrap(Vec::new());
+        expected.write_event(&event).unwrap();
+        assert_eq!(writer.into_vec(), expected.into_vec());
+    }
+
+    #[test]
+    fn test_write_summary_counted() {
+        let mut buf = Vec::new();
+        let mut writer = Writer::wrap(&mut buf);
+        writer.write_file_version().unwrap();
+        let mut counts = Vec::new();
+        for step in 0..3 {
+            let summ = crate::SummaryBuilder::new()
+                .text("notes", &"x".repeat(step * 100))
+                .build();
+            let before = writer.get_ref().len();
+            let count = writer
+                .write_summary_counted(SystemTime::now(), 0, summ)
+                .unwrap();
+            assert_eq!(count, writer.get_ref().len() - before);
+            counts.push(count);
+        }
+        assert!(
+            counts[0] < counts[1] && counts[1] < counts[2],
+            "{:?}",
+            counts
+        );
+    }
+
+    #[test]
+    fn test_skip_empty_summaries() {
+        let mut writer = Writer::wrap(Vec::new()).with_skip_empty_summaries();
+        let empty = crate::SummaryBuilder::new().build();
+        writer
+            .write_summary(SystemTime::now(), 0, empty.clone())
+            .unwrap();
+        assert!(writer.get_ref().is_empty());
+        let count = writer
+            .write_summary_counted(SystemTime::now(), 1, empty.clone())
+            .unwrap();
+        assert_eq!(count, 0);
+        let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+        writer.write_summary(SystemTime::now(), 2, summ).unwrap();
+        let steps: Vec<i64> = EventReader::new(&writer.into_vec()[..])
+            .map(|e| e.unwrap().step)
+            .collect();
+        assert_eq!(steps, vec![2]);
+
+        // By default, empty summaries are still written.
+        let mut writer = Writer::wrap(Vec::new());
+        writer.write_summary(SystemTime::now(), 0, empty).unwrap();
+        assert!(!writer.get_ref().is_empty());
+    }
+
+    #[test]
+    fn test_write_graph() {
+        let graph = pb::GraphDef {
+            node: vec![
+                pb::NodeDef {
+                    name: "x".to_string(),
+                    op: "Placeholder".to_string(),
+                    ..Default::default()
+                },
+                pb::NodeDef {
+                    name: "y".to_string(),
+                    op: "Relu".to_string(),
+                    input: vec!["x".to_string()],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        let mut writer = Writer::wrap(Vec::new());
+        writer
+            .write_graph(SystemTime::now(), graph.clone())
+            .unwrap();
+        let events: Vec<pb::Event> = EventReader::new(&writer.into_vec()[..])
+            .map(Result::unwrap)
+            .collect();
+        match &events[0].what {
+            Some(pb::event::What::GraphDef(bytes)) => {
+                assert_eq!(pb::GraphDef::decode(&bytes[..]).unwrap(), graph)
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_write_layout() {
+        let layout = pb::Layout {
+            category: vec![pb::Category {
+                title: "losses".to_string(),
+                chart: vec![pb::Chart {
+                    title: "margin".to_string(),
+                    content: Some(pb::chart::Content::Margin(pb::MarginChartContent {
+                        series: vec![pb::margin_chart_content::Series {
+                            value: "loss".to_string(),
+                            lower: "loss/lower".to_string(),
+                            upper: "loss/upper".to_string(),
+                        }],
+                    })),
+                }],
+                closed: true,
+            }],
+            ..Default::default()
+        };
+        let mut writer = Writer::wrap(Vec::new());
+        writer.write_layout(layout.clone()).unwrap();
+        let events: Vec<pb::Event> = EventReader::new(&writer.into_vec()[..])
+            .map(Result::unwrap)
+            .collect();
+        let value = match &events[0].what {
+            Some(pb::event::What::Summary(s)) => s.value[0].clone(),
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(value.tag, CUSTOM_SCALARS_TAG);
+        let plugin_data = value.metadata.unwrap().plugin_data.unwrap();
+        assert_eq!(plugin_data.plugin_name, "custom_scalars");
+        let tensor = match value.value {
+            Some(pb::summary::value::Value::Tensor(t)) => t,
+            other => panic!("{:?}", other),
+        };
+        assert_eq!(
+            pb::Layout::decode(&tensor.string_val[0][..]).unwrap(),
+            layout
+        );
+    }
+
+    #[test]
+    fn test_strict_file_version() {
+        let mut writer = Writer::wrap(Vec::new()).with_strict_file_version();
+        assert!(!writer.has_file_version());
+        writer.write_file_version().unwrap();
+        assert!(writer.has_file_version());
+        let err = writer.write_file_version().unwrap_err();
+        assert_eq!(io::Error::from(err).kind(), io::ErrorKind::AlreadyExists);
+        let events = EventReader::new(&writer.into_vec()[..]).count();
+        assert_eq!(events, 1);
+
+        // Without strict mode, a duplicate header is still written.
+        let mut writer = Writer::wrap(Vec::new());
+        writer.write_file_version().unwrap();
+        writer.write_file_version().unwrap();
+        assert_eq!(EventReader::new(&writer.into_vec()[..]).count(), 2);
+    }
+
+    #[test]
+    fn test_filtered_file_version() {
+        let mut writer = Writer::wrap(Vec::new())
+            .with_strict_file_version()
+            .with_filter(|event| event.step > 0);
+        writer.write_file_version().unwrap();
+        assert!(!writer.has_file_version());
+        // Nothing was written, so strict mode has nothing to object to.
+        let mut writer = writer.with_filter(|_| true);
+        writer.write_file_version().unwrap();
+        assert!(writer.has_file_version());
+        assert_eq!(EventReader::new(&writer.into_vec()[..]).count(), 1);
+    }
+
+    #[test]
+    fn test_write_summary_encoded() {
+        let t = std::time::UNIX_EPOCH + Duration::from_secs(100);
+        let builder = || crate::SummaryBuilder::new().scalar("loss", 0.5);
+        let mut decoded = Writer::wrap(Vec::new());
+        decoded.write_summary(t, 7, builder().build()).unwrap();
+        let mut encoded = Writer::wrap(Vec::new());
+        encoded
+            .write_summary_encoded(t, 7, &builder().build_encoded())
+            .unwrap();
+        assert_eq!(encoded.into_vec(), decoded.into_vec());
+    }
+
+    #[test]
+    fn test_with_filter() {
+        let mut writer = Writer::wrap(Vec::new()).with_filter(|event| event.step % 2 == 0);
+        for step in 0..5 {
+            let summ = crate::SummaryBuilder::new().scalar("loss", 1.0).build();
+            writer.write_summary(SystemTime::now(), step, summ).unwrap();
+        }
+        let steps: Vec<i64> = EventReader::new(&writer.into_vec()[..])
+            .map(|e| e.unwrap().step)
+            .collect();
+        assert_eq!(steps, vec![0, 2, 4]);
+    }
+
+    #[test]
+    fn test_new_for_rank() {
+        let dir = TempDir::create();
+        Writer::new_for_rank(dir.path(), 0).unwrap();
+        Writer::new_for_rank(dir.path(), 1).unwrap();
+        let mut names = file_names(dir.path());
+        names.sort();
+        assert_eq!(names.len(), 2);
+        assert_ne!(names[0], names[1]);
+        for (rank, name) in names.iter().enumerate() {
+            assert!(name.starts_with("events.out.tfevents."), "{}", name);
+            assert!(name.contains(&format!(".rank{}.", rank)), "{}", name);
+        }
+    }
 }